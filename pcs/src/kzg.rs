@@ -0,0 +1,424 @@
+//! KZG commitments to univariate polynomials over a pairing curve
+//! commit(p) = p(tau).G, an opening of p at z is a commitment to the quotient
+//! q(X) = (p(X) - p(z)) / (X - z), checked with e(C - p(z).G, H) == e(W, tau.H - z.H)
+
+use ark_ec::pairing::Pairing;
+use ark_ec::scalar_mul::variable_base::VariableBaseMSM;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{Field, One, UniformRand, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
+use ark_std::rand::Rng;
+use polynomial::encoding::{self, Endianness};
+use polynomial::univariate_poly::UnivariatePolynomial;
+use std::marker::PhantomData;
+use transcript::channel::InteractiveChannel;
+
+/// Structured reference string
+/// powers of tau in G1 (enough to commit to polynomials up to max_degree) and tau in G2
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize)]
+pub struct KZGParams<E: Pairing> {
+    powers_of_g: Vec<E::G1Affine>,
+    h: E::G2Affine,
+    tau_h: E::G2Affine,
+}
+
+impl<E: Pairing> KZGParams<E> {
+    /// Builds the params from existing powers (e.g. loaded from a ceremony)
+    /// powers_of_g = [G, tau.G, tau^2.G, ...], h = H, tau_h = tau.H
+    pub fn new(
+        powers_of_g: Vec<E::G1Affine>,
+        h: E::G2Affine,
+        tau_h: E::G2Affine,
+    ) -> Result<Self, &'static str> {
+        if powers_of_g.is_empty() {
+            return Err("kzg params need at least one power of tau");
+        }
+
+        Ok(Self {
+            powers_of_g,
+            h,
+            tau_h,
+        })
+    }
+
+    /// Returns the max degree of a polynomial that can be committed to
+    pub fn max_degree(&self) -> usize {
+        self.powers_of_g.len() - 1
+    }
+}
+
+impl<E: Pairing> Valid for KZGParams<E> {
+    fn check(&self) -> Result<(), SerializationError> {
+        if self.powers_of_g.is_empty() {
+            return Err(SerializationError::InvalidData);
+        }
+        self.powers_of_g.check()?;
+        self.h.check()?;
+        self.tau_h.check()
+    }
+}
+
+/// Deserializes through `KZGParams::new`, so empty powers are rejected even when
+/// deserializing without validation
+impl<E: Pairing> CanonicalDeserialize for KZGParams<E> {
+    fn deserialize_with_mode<R: std::io::Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let powers_of_g = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let h = E::G2Affine::deserialize_with_mode(&mut reader, compress, validate)?;
+        let tau_h = E::G2Affine::deserialize_with_mode(&mut reader, compress, validate)?;
+        Self::new(powers_of_g, h, tau_h).map_err(|_| SerializationError::InvalidData)
+    }
+}
+
+/// Commitment to a univariate polynomial
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KZGCommitment<E: Pairing>(pub E::G1Affine);
+
+/// Opening proof, the commitment to the quotient polynomial
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KZGProof<E: Pairing>(pub E::G1Affine);
+
+/// Univariate KZG commitment scheme
+pub struct KZG<E: Pairing> {
+    _marker: PhantomData<E>,
+}
+
+impl<E: Pairing> KZG<E> {
+    /// Generates params for polynomials up to max_degree from a random tau
+    /// NOTE: the caller learns tau, only use this for tests and benchmarks
+    pub fn setup<R: Rng>(max_degree: usize, rng: &mut R) -> KZGParams<E> {
+        let tau = E::ScalarField::rand(rng);
+        let g = E::G1::rand(rng);
+        let h = E::G2::rand(rng);
+
+        let mut powers = Vec::with_capacity(max_degree + 1);
+        let mut power = E::ScalarField::one();
+        for _ in 0..=max_degree {
+            powers.push(g * power);
+            power *= tau;
+        }
+
+        KZGParams {
+            powers_of_g: E::G1::normalize_batch(&powers),
+            h: h.into_affine(),
+            tau_h: (h * tau).into_affine(),
+        }
+    }
+
+    /// Commit to a polynomial
+    pub fn commit(
+        params: &KZGParams<E>,
+        poly: &UnivariatePolynomial<E::ScalarField>,
+    ) -> Result<KZGCommitment<E>, &'static str> {
+        let coefficients = poly.coefficients();
+        // zero high degree coefficients are allowed past the max degree
+        if coefficients
+            .iter()
+            .skip(params.powers_of_g.len())
+            .any(|coefficient| !coefficient.is_zero())
+        {
+            return Err("polynomial degree exceeds the kzg params max degree");
+        }
+
+        let length = coefficients.len().min(params.powers_of_g.len());
+        let commitment =
+            E::G1::msm_unchecked(&params.powers_of_g[..length], &coefficients[..length]);
+        Ok(KZGCommitment(commitment.into_affine()))
+    }
+
+    /// Open the polynomial at a point, returns the evaluation and the opening proof
+    pub fn open(
+        params: &KZGParams<E>,
+        poly: &UnivariatePolynomial<E::ScalarField>,
+        point: &E::ScalarField,
+    ) -> Result<(E::ScalarField, KZGProof<E>), &'static str> {
+        let value = poly.evaluate(point);
+        let quotient = divide_by_linear(poly.coefficients(), point);
+        let witness = Self::commit(params, &UnivariatePolynomial::new(quotient))?;
+        Ok((value, KZGProof(witness.0)))
+    }
+
+    /// Verify that the committed polynomial evaluates to value at point
+    pub fn verify(
+        params: &KZGParams<E>,
+        commitment: &KZGCommitment<E>,
+        point: &E::ScalarField,
+        value: &E::ScalarField,
+        proof: &KZGProof<E>,
+    ) -> bool {
+        let g = params.powers_of_g[0];
+        let lhs = E::pairing(commitment.0.into_group() - g * value, params.h);
+        let rhs = E::pairing(proof.0, params.tau_h.into_group() - params.h * point);
+        lhs == rhs
+    }
+
+    /// Open several polynomials at the same point with a single proof
+    /// the polynomials are combined as sum_i gamma^i.p_i, gamma is sampled from the transcript
+    /// after it absorbs the commitments, the point and the evaluations
+    /// commitments[i] must be the commitment to polys[i]
+    pub fn batch_open<C: InteractiveChannel>(
+        params: &KZGParams<E>,
+        polys: &[UnivariatePolynomial<E::ScalarField>],
+        commitments: &[KZGCommitment<E>],
+        point: &E::ScalarField,
+        transcript: &mut C,
+    ) -> Result<(Vec<E::ScalarField>, KZGProof<E>), &'static str> {
+        if polys.is_empty() {
+            return Err("batch open requires at least one polynomial");
+        }
+        if polys.len() != commitments.len() {
+            return Err("batch open requires a commitment for every polynomial");
+        }
+
+        let values = polys
+            .iter()
+            .map(|poly| poly.evaluate(point))
+            .collect::<Vec<_>>();
+        let gamma = Self::batch_challenge(commitments, point, &values, transcript)?;
+        let mut combined = vec![];
+        for (poly, power) in polys.iter().zip(powers_of(&gamma)) {
+            let coefficients = poly.coefficients();
+            if combined.len() < coefficients.len() {
+                combined.resize(coefficients.len(), E::ScalarField::zero());
+            }
+            for (acc, coefficient) in combined.iter_mut().zip(coefficients) {
+                *acc += power * coefficient;
+            }
+        }
+
+        let (_, proof) = Self::open(params, &UnivariatePolynomial::new(combined), point)?;
+        Ok((values, proof))
+    }
+
+    /// Verify a `batch_open` proof
+    /// the transcript must be in the state the prover's was in when it called `batch_open`
+    pub fn batch_verify<C: InteractiveChannel>(
+        params: &KZGParams<E>,
+        commitments: &[KZGCommitment<E>],
+        point: &E::ScalarField,
+        values: &[E::ScalarField],
+        proof: &KZGProof<E>,
+        transcript: &mut C,
+    ) -> bool {
+        if commitments.is_empty() || commitments.len() != values.len() {
+            return false;
+        }
+        let Ok(gamma) = Self::batch_challenge(commitments, point, values, transcript) else {
+            return false;
+        };
+
+        let powers = powers_of(&gamma)
+            .take(commitments.len())
+            .collect::<Vec<_>>();
+        let bases = commitments
+            .iter()
+            .map(|commitment| commitment.0)
+            .collect::<Vec<_>>();
+        let commitment = E::G1::msm_unchecked(&bases, &powers).into_affine();
+        let value = values
+            .iter()
+            .zip(powers.iter())
+            .map(|(value, power)| *value * power)
+            .sum();
+
+        Self::verify(params, &KZGCommitment(commitment), point, &value, proof)
+    }
+
+    /// Absorbs the batch opening claim (commitments, point and evaluations) and samples gamma
+    fn batch_challenge<C: InteractiveChannel>(
+        commitments: &[KZGCommitment<E>],
+        point: &E::ScalarField,
+        values: &[E::ScalarField],
+        transcript: &mut C,
+    ) -> Result<E::ScalarField, &'static str> {
+        let mut bytes = b"kzg/batch-open".to_vec();
+        encoding::append_length(&mut bytes, commitments.len(), Endianness::Big);
+        for commitment in commitments {
+            commitment
+                .serialize_compressed(&mut bytes)
+                .map_err(|_| "failed to serialize the commitment")?;
+        }
+        encoding::append_field_element(&mut bytes, point, Endianness::Big);
+        encoding::append_field_elements(&mut bytes, values, Endianness::Big);
+        transcript.append(bytes.as_slice())?;
+        transcript.challenge()
+    }
+}
+
+/// Returns 1, x, x^2, ...
+fn powers_of<F: Field>(x: &F) -> impl Iterator<Item = F> + '_ {
+    std::iter::successors(Some(F::one()), move |power| Some(*power * x))
+}
+
+/// Computes (p(X) - p(z)) / (X - z) by synthetic division, the remainder p(z) is dropped
+fn divide_by_linear<F: Field>(coefficients: &[F], point: &F) -> Vec<F> {
+    if coefficients.len() < 2 {
+        return vec![];
+    }
+
+    let mut quotient = vec![F::zero(); coefficients.len() - 1];
+    let mut carry = F::zero();
+    for i in (1..coefficients.len()).rev() {
+        carry = coefficients[i] + carry * point;
+        quotient[i - 1] = carry;
+    }
+    quotient
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{divide_by_linear, KZGCommitment, KZGParams, KZGProof, KZG};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::UniformRand;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use polynomial::univariate_poly::UnivariatePolynomial;
+    use transcript::Transcript;
+
+    fn random_poly(degree: usize) -> UnivariatePolynomial<Fr> {
+        let mut rng = ark_std::test_rng();
+        UnivariatePolynomial::new((0..=degree).map(|_| Fr::rand(&mut rng)).collect())
+    }
+
+    #[test]
+    fn test_divide_by_linear() {
+        // x^2 + 3x + 2 = (x + 1)(x + 2)
+        let quotient = divide_by_linear(&[Fr::from(2), Fr::from(3), Fr::from(1)], &-Fr::from(1));
+        assert_eq!(quotient, vec![Fr::from(2), Fr::from(1)]);
+        assert!(divide_by_linear(&[Fr::from(5)], &Fr::from(1)).is_empty());
+    }
+
+    #[test]
+    fn test_open_verify() {
+        let mut rng = ark_std::test_rng();
+        let params = KZG::<Bls12_381>::setup(8, &mut rng);
+        let poly = random_poly(8);
+        let commitment = KZG::commit(&params, &poly).unwrap();
+        let point = Fr::rand(&mut rng);
+
+        let (value, proof) = KZG::open(&params, &poly, &point).unwrap();
+        assert_eq!(value, poly.evaluate(&point));
+        assert!(KZG::verify(&params, &commitment, &point, &value, &proof));
+        assert!(!KZG::verify(
+            &params,
+            &commitment,
+            &point,
+            &(value + Fr::from(1)),
+            &proof
+        ));
+        assert!(!KZG::verify(
+            &params,
+            &commitment,
+            &(point + Fr::from(1)),
+            &value,
+            &proof
+        ));
+
+        // degree above the params is rejected
+        assert!(KZG::commit(&params, &random_poly(9)).is_err());
+        // zero high coefficients don't count towards the degree
+        let mut padded = poly.coefficients().to_vec();
+        padded.push(Fr::from(0));
+        assert_eq!(
+            KZG::commit(&params, &UnivariatePolynomial::new(padded)).unwrap(),
+            commitment
+        );
+    }
+
+    #[test]
+    fn test_batch_open_verify() {
+        let mut rng = ark_std::test_rng();
+        let params = KZG::<Bls12_381>::setup(6, &mut rng);
+        let polys = vec![random_poly(6), random_poly(3), random_poly(0)];
+        let commitments = polys
+            .iter()
+            .map(|poly| KZG::commit(&params, poly).unwrap())
+            .collect::<Vec<_>>();
+        let point = Fr::rand(&mut rng);
+
+        let (values, proof) = KZG::batch_open(
+            &params,
+            &polys,
+            &commitments,
+            &point,
+            &mut Transcript::new(),
+        )
+        .unwrap();
+        let verify = |commitments: &[KZGCommitment<Bls12_381>],
+                      values: &[Fr],
+                      transcript: &mut Transcript| {
+            KZG::batch_verify(&params, commitments, &point, values, &proof, transcript)
+        };
+        assert!(verify(&commitments, &values, &mut Transcript::new()));
+
+        let mut wrong_values = values.clone();
+        wrong_values[1] += Fr::from(1);
+        assert!(!verify(&commitments, &wrong_values, &mut Transcript::new()));
+        assert!(!verify(&commitments[..2], &values, &mut Transcript::new()));
+
+        // gamma depends on the claim and on everything absorbed before it
+        let mut swapped = commitments.clone();
+        swapped.swap(0, 1);
+        let mut swapped_values = values.clone();
+        swapped_values.swap(0, 1);
+        assert!(!verify(&swapped, &swapped_values, &mut Transcript::new()));
+        let mut transcript = Transcript::new();
+        transcript.append(b"other context");
+        assert!(!verify(&commitments, &values, &mut transcript));
+
+        assert!(KZG::batch_open(
+            &params,
+            &polys,
+            &commitments[..2],
+            &point,
+            &mut Transcript::new()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut rng = ark_std::test_rng();
+        let params = KZG::<Bls12_381>::setup(4, &mut rng);
+        let poly = random_poly(4);
+        let commitment = KZG::commit(&params, &poly).unwrap();
+        let (_, proof) = KZG::open(&params, &poly, &Fr::from(3)).unwrap();
+
+        let mut bytes = vec![];
+        params.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            KZGParams::<Bls12_381>::deserialize_compressed(bytes.as_slice()).unwrap(),
+            params
+        );
+
+        // empty powers are rejected, with or without validation
+        let empty = KZGParams::<Bls12_381> {
+            powers_of_g: vec![],
+            h: params.h,
+            tau_h: params.tau_h,
+        };
+        let mut bytes = vec![];
+        empty.serialize_compressed(&mut bytes).unwrap();
+        assert!(KZGParams::<Bls12_381>::deserialize_compressed(bytes.as_slice()).is_err());
+        assert!(
+            KZGParams::<Bls12_381>::deserialize_compressed_unchecked(bytes.as_slice()).is_err()
+        );
+
+        let mut bytes = vec![];
+        commitment.serialize_compressed(&mut bytes).unwrap();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let mut reader = bytes.as_slice();
+        assert_eq!(
+            KZGCommitment::<Bls12_381>::deserialize_compressed(&mut reader).unwrap(),
+            commitment
+        );
+        assert_eq!(
+            KZGProof::<Bls12_381>::deserialize_compressed(&mut reader).unwrap(),
+            proof
+        );
+    }
+}
@@ -0,0 +1,4 @@
+//! Polynomial commitment schemes
+
+pub mod kzg;
+pub mod ptau;
@@ -0,0 +1,309 @@
+//! Loader for snarkjs powers of tau (.ptau) ceremony files
+//!
+//! Layout (all integers little endian):
+//! - magic "ptau", version (u32), section count (u32)
+//! - sections: type (u32), size (u64), data
+//!   - 1 header: n8 (u32), base field modulus (n8 bytes), power (u32), ceremony power (u32)
+//!   - 2 tau G1: 2^(power + 1) - 1 points [G, tau.G, tau^2.G, ...]
+//!   - 3 tau G2: 2^power points [H, tau.H, ...]
+//!
+//! Points are stored uncompressed as affine coordinates, each base field element is
+//! n8 bytes in montgomery form (G2 coordinates are c0 followed by c1).
+//! Only the header and the powers needed for the requested degree are read.
+
+use crate::kzg::KZGParams;
+use ark_ec::pairing::Pairing;
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::{BigInteger, Field, PrimeField};
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const HEADER_SECTION: u32 = 1;
+const TAU_G1_SECTION: u32 = 2;
+const TAU_G2_SECTION: u32 = 3;
+
+/// Load KZG params for polynomials up to max_degree from a .ptau file
+pub fn load_ptau_file<E, P1, P2>(
+    path: impl AsRef<Path>,
+    max_degree: usize,
+) -> Result<KZGParams<E>, &'static str>
+where
+    E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+    P1: SWCurveConfig,
+    P2: SWCurveConfig<BaseField: Field<BasePrimeField = BaseField<P1>>>,
+{
+    let file = std::fs::File::open(path).map_err(|_| "failed to open ptau file")?;
+    load_ptau(&mut BufReader::new(file), max_degree)
+}
+
+/// Load KZG params for polynomials up to max_degree from .ptau encoded data
+/// every point is checked to be on the curve and in the prime order subgroup,
+/// the first powers are also checked to share the same tau
+pub fn load_ptau<E, P1, P2, R>(
+    reader: &mut R,
+    max_degree: usize,
+) -> Result<KZGParams<E>, &'static str>
+where
+    E: Pairing<G1Affine = Affine<P1>, G2Affine = Affine<P2>>,
+    P1: SWCurveConfig,
+    P2: SWCurveConfig<BaseField: Field<BasePrimeField = BaseField<P1>>>,
+    R: Read + Seek,
+{
+    let mut magic = [0; 4];
+    read_exact(reader, &mut magic)?;
+    if &magic != b"ptau" {
+        return Err("invalid ptau magic");
+    }
+    let _version = read_u32(reader)?;
+    let n_sections = read_u32(reader)?;
+
+    // section type -> data offset, only the first occurrence of a section is used
+    let mut sections = vec![];
+    for _ in 0..n_sections {
+        let section_type = read_u32(reader)?;
+        let size = read_u64(reader)?;
+        let offset = reader
+            .stream_position()
+            .map_err(|_| "failed to read ptau file")?;
+        sections.push((section_type, offset, size));
+        reader
+            .seek(SeekFrom::Current(size as i64))
+            .map_err(|_| "truncated ptau section")?;
+    }
+    let section = |section_type: u32| {
+        sections
+            .iter()
+            .find(|(ty, _, _)| *ty == section_type)
+            .map(|(_, offset, size)| (*offset, *size))
+            .ok_or("missing ptau section")
+    };
+
+    // header
+    let (offset, _) = section(HEADER_SECTION)?;
+    seek(reader, offset)?;
+    let n8 = read_u32(reader)? as usize;
+    let field_size = field_size::<BaseField<P1>>();
+    if n8 != field_size {
+        return Err("ptau base field size does not match the curve");
+    }
+    let mut modulus = vec![0; n8];
+    read_exact(reader, &mut modulus)?;
+    if modulus != BaseField::<P1>::MODULUS.to_bytes_le()[..n8] {
+        return Err("ptau base field modulus does not match the curve");
+    }
+    let power = read_u32(reader)?;
+    if power >= 63 {
+        return Err("invalid ptau power");
+    }
+    let g1_count = (1_u64 << (power + 1)) - 1;
+    if max_degree as u64 >= g1_count {
+        return Err("ptau file does not have enough powers for the requested degree");
+    }
+
+    let r_inv = montgomery_r_inverse::<BaseField<P1>>(n8);
+
+    // tau G1, only the first max_degree + 1 powers
+    let (offset, size) = section(TAU_G1_SECTION)?;
+    let g1_size = 2 * n8;
+    if size < (max_degree as u64 + 1) * g1_size as u64 {
+        return Err("truncated ptau tau g1 section");
+    }
+    seek(reader, offset)?;
+    let mut buffer = vec![0; g1_size];
+    let mut powers_of_g = Vec::with_capacity(max_degree + 1);
+    for _ in 0..=max_degree {
+        read_exact(reader, &mut buffer)?;
+        powers_of_g.push(read_point::<P1>(&buffer, n8, &r_inv)?);
+    }
+
+    // tau G2, only H and tau.H are needed
+    let (offset, size) = section(TAU_G2_SECTION)?;
+    let g2_size = 4 * n8;
+    if size < 2 * g2_size as u64 {
+        return Err("truncated ptau tau g2 section");
+    }
+    seek(reader, offset)?;
+    let mut buffer = vec![0; g2_size];
+    read_exact(reader, &mut buffer)?;
+    let h = read_point::<P2>(&buffer, n8, &r_inv)?;
+    read_exact(reader, &mut buffer)?;
+    let tau_h = read_point::<P2>(&buffer, n8, &r_inv)?;
+
+    // e(tau.G, H) == e(G, tau.H)
+    if max_degree > 0 && E::pairing(powers_of_g[1], h) != E::pairing(powers_of_g[0], tau_h) {
+        return Err("ptau g1 and g2 powers use different tau");
+    }
+
+    KZGParams::new(powers_of_g, h, tau_h)
+}
+
+/// Base prime field of a curve's coordinates
+type BaseField<P> = <<P as ark_ec::CurveConfig>::BaseField as Field>::BasePrimeField;
+
+/// Decodes an affine point from montgomery form coordinates
+/// and checks that it is on the curve and in the prime order subgroup
+fn read_point<P: SWCurveConfig>(
+    bytes: &[u8],
+    n8: usize,
+    r_inv: &BaseField<P>,
+) -> Result<Affine<P>, &'static str> {
+    let mut elements = Vec::with_capacity(bytes.len() / n8);
+    for chunk in bytes.chunks(n8) {
+        let montgomery = BaseField::<P>::from_le_bytes_mod_order(chunk);
+        if montgomery.into_bigint().to_bytes_le()[..n8] != *chunk {
+            return Err("ptau coordinate is not a canonical field element");
+        }
+        elements.push(montgomery * r_inv);
+    }
+
+    let (x, y) = elements.split_at(elements.len() / 2);
+    let x = P::BaseField::from_base_prime_field_elems(x.iter().copied())
+        .ok_or("invalid ptau coordinate")?;
+    let y = P::BaseField::from_base_prime_field_elems(y.iter().copied())
+        .ok_or("invalid ptau coordinate")?;
+    let point = Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err("invalid ptau curve point");
+    }
+    Ok(point)
+}
+
+/// Number of bytes used to store a field element
+fn field_size<F: PrimeField>() -> usize {
+    (F::MODULUS_BIT_SIZE as usize).div_ceil(64) * 8
+}
+
+/// R^-1 where R = 2^(8 * n8) is the montgomery factor of the stored elements
+fn montgomery_r_inverse<F: PrimeField>(n8: usize) -> F {
+    F::from(2_u64)
+        .pow([8 * n8 as u64])
+        .inverse()
+        .expect("2 is invertible in an odd prime field")
+}
+
+fn seek<R: Seek>(reader: &mut R, offset: u64) -> Result<(), &'static str> {
+    reader
+        .seek(SeekFrom::Start(offset))
+        .map(|_| ())
+        .map_err(|_| "failed to seek in ptau file")
+}
+
+fn read_exact<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<(), &'static str> {
+    reader
+        .read_exact(buffer)
+        .map_err(|_| "unexpected end of ptau file")
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, &'static str> {
+    let mut bytes = [0; 4];
+    read_exact(reader, &mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, &'static str> {
+    let mut bytes = [0; 8];
+    read_exact(reader, &mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::load_ptau;
+    use crate::kzg::KZG;
+    use ark_bls12_381::{Bls12_381, Fq, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+    use ark_ec::{CurveGroup, PrimeGroup};
+    use ark_ff::{BigInteger, Field, PrimeField, UniformRand};
+    use polynomial::univariate_poly::UnivariatePolynomial;
+    use std::io::Cursor;
+
+    const N8: usize = 48;
+
+    fn montgomery(element: &Fq) -> Vec<u8> {
+        let r = Fq::from(2_u64).pow([8 * N8 as u64]);
+        (*element * r).into_bigint().to_bytes_le()[..N8].to_vec()
+    }
+
+    fn section(bytes: &mut Vec<u8>, section_type: u32, data: &[u8]) {
+        bytes.extend(section_type.to_le_bytes());
+        bytes.extend((data.len() as u64).to_le_bytes());
+        bytes.extend(data);
+    }
+
+    /// Writes a .ptau file for the given tau, sections are written out of order
+    fn ptau_bytes(power: u32, tau: Fr) -> Vec<u8> {
+        let mut header = (N8 as u32).to_le_bytes().to_vec();
+        header.extend(&Fq::MODULUS.to_bytes_le()[..N8]);
+        header.extend(power.to_le_bytes());
+        header.extend(power.to_le_bytes());
+
+        let mut tau_g1 = vec![];
+        let mut power_of_tau = Fr::from(1);
+        for _ in 0..(1 << (power + 1)) - 1 {
+            let point: G1Affine = (G1Projective::generator() * power_of_tau).into_affine();
+            tau_g1.extend(montgomery(&point.x));
+            tau_g1.extend(montgomery(&point.y));
+            power_of_tau *= tau;
+        }
+
+        let mut tau_g2 = vec![];
+        let mut power_of_tau = Fr::from(1);
+        for _ in 0..1 << power {
+            let point: G2Affine = (G2Projective::generator() * power_of_tau).into_affine();
+            for coordinate in [point.x.c0, point.x.c1, point.y.c0, point.y.c1] {
+                tau_g2.extend(montgomery(&coordinate));
+            }
+            power_of_tau *= tau;
+        }
+
+        let mut bytes = b"ptau".to_vec();
+        bytes.extend(1_u32.to_le_bytes());
+        bytes.extend(3_u32.to_le_bytes());
+        section(&mut bytes, 2, &tau_g1);
+        section(&mut bytes, 1, &header);
+        section(&mut bytes, 3, &tau_g2);
+        bytes
+    }
+
+    #[test]
+    fn test_load_ptau() {
+        let mut rng = ark_std::test_rng();
+        let bytes = ptau_bytes(2, Fr::rand(&mut rng));
+
+        let params = load_ptau::<Bls12_381, _, _, _>(&mut Cursor::new(&bytes), 4).unwrap();
+        assert_eq!(params.max_degree(), 4);
+
+        let poly = UnivariatePolynomial::new((0..5).map(|_| Fr::rand(&mut rng)).collect());
+        let commitment = KZG::commit(&params, &poly).unwrap();
+        let point = Fr::rand(&mut rng);
+        let (value, proof) = KZG::open(&params, &poly, &point).unwrap();
+        assert!(KZG::verify(&params, &commitment, &point, &value, &proof));
+
+        // 2^3 - 1 tau g1 powers, max degree 6
+        assert!(load_ptau::<Bls12_381, _, _, _>(&mut Cursor::new(&bytes), 6).is_ok());
+        assert!(load_ptau::<Bls12_381, _, _, _>(&mut Cursor::new(&bytes), 7).is_err());
+    }
+
+    #[test]
+    fn test_load_ptau_rejects_invalid_files() {
+        let bytes = ptau_bytes(1, Fr::from(5));
+        let load = |bytes: &[u8]| load_ptau::<Bls12_381, _, _, _>(&mut Cursor::new(bytes), 2);
+        assert!(load(&bytes).is_ok());
+
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] = b'x';
+        assert!(load(&wrong_magic).is_err());
+
+        assert!(load(&bytes[..bytes.len() - 1]).is_err());
+
+        // corrupt the second tau g1 point (offset: file header 12, section header 12, point 96)
+        let mut off_curve = bytes.clone();
+        off_curve[12 + 12 + 96] ^= 1;
+        assert!(load(&off_curve).is_err());
+
+        // only the requested powers are read, corrupting a later power goes unnoticed
+        let mut unread = bytes.clone();
+        unread[12 + 12 + 2 * 96] ^= 1;
+        assert!(load_ptau::<Bls12_381, _, _, _>(&mut Cursor::new(&unread), 1).is_ok());
+        assert!(load(&unread).is_err());
+    }
+}
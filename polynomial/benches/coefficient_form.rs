@@ -0,0 +1,60 @@
+use ark_bls12_381::Fr;
+use ark_std::test_rng;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use polynomial::multilinear::coefficient_form::{
+    CoeffMultilinearPolynomial, InterpolationStrategy,
+};
+
+pub fn bench_index_checker(c: &mut Criterion) {
+    // one checker per hypercube point of a 14 var space, about the wiring of a 10k gate layer
+    c.bench_function("index_checker_14_vars_10k_points", |b| {
+        b.iter(|| {
+            for index in 0..10_000 {
+                black_box(CoeffMultilinearPolynomial::<Fr>::index_checker(index, 14));
+            }
+        });
+    });
+}
+
+pub fn bench_interpolate(c: &mut Criterion) {
+    c.bench_function("coefficient_interpolate_8_vars", |b| {
+        let mut rng = test_rng();
+        let values = (0..256)
+            .map(|_| <Fr as ark_std::UniformRand>::rand(&mut rng))
+            .collect::<Vec<_>>();
+        b.iter(|| black_box(CoeffMultilinearPolynomial::interpolate(&values)));
+    });
+}
+
+pub fn bench_interpolation_strategies(c: &mut Criterion) {
+    let mut group = c.benchmark_group("coefficient_interpolate_strategy");
+    group.sample_size(10);
+    let mut rng = test_rng();
+    for (strategy, n_vars) in [
+        (InterpolationStrategy::LagrangeBasis, 10),
+        (InterpolationStrategy::Mobius, 10),
+        (InterpolationStrategy::Mobius, 16),
+        (InterpolationStrategy::Mobius, 18),
+        (InterpolationStrategy::Mobius, 20),
+    ] {
+        let values = (0..1 << n_vars)
+            .map(|_| <Fr as ark_std::UniformRand>::rand(&mut rng))
+            .collect::<Vec<_>>();
+        group.bench_function(format!("{strategy:?}_{n_vars}_vars"), |b| {
+            b.iter(|| {
+                black_box(CoeffMultilinearPolynomial::interpolate_with(
+                    &values, strategy,
+                ))
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_index_checker,
+    bench_interpolate,
+    bench_interpolation_strategies
+);
+criterion_main!(benches);
@@ -62,22 +62,22 @@ pub fn arkworks_field_op_benchmark(_c: &mut Criterion) {
     start_tscope!("arkworks_evaluate");
     start_tscope!("poly_eval 18var");
     let (poly, to_eval) = ark_random_poly_evaluation_pair::<FTr>(18);
-    poly.fix_variables(&to_eval.as_slice());
+    poly.fix_variables(to_eval.as_slice());
     end_tscope!();
 
     start_tscope!("poly_eval 19var");
     let (poly, to_eval) = ark_random_poly_evaluation_pair::<FTr>(19);
-    poly.fix_variables(&to_eval.as_slice());
+    poly.fix_variables(to_eval.as_slice());
     end_tscope!();
 
     start_tscope!("poly_eval 20var");
     let (poly, to_eval) = ark_random_poly_evaluation_pair::<FTr>(20);
-    poly.fix_variables(&to_eval.as_slice());
+    poly.fix_variables(to_eval.as_slice());
     end_tscope!();
 
     start_tscope!("poly_eval 21var");
     let (poly, to_eval) = ark_random_poly_evaluation_pair::<FTr>(21);
-    poly.fix_variables(&to_eval.as_slice());
+    poly.fix_variables(to_eval.as_slice());
     end_tscope!();
     end_tscope!();
 }
@@ -108,22 +108,22 @@ pub fn poly_field_op_benchmark(_c: &mut Criterion) {
     start_tscope!("poly_evaluate");
     start_tscope!("poly_eval 18var");
     let (poly, to_eval) = poly_eval_pair::<FTr>(18);
-    poly.evaluate(&to_eval.as_slice());
+    poly.evaluate(to_eval.as_slice()).unwrap();
     end_tscope!();
 
     start_tscope!("poly_eval 19var");
     let (poly, to_eval) = poly_eval_pair::<FTr>(19);
-    poly.evaluate(&to_eval.as_slice());
+    poly.evaluate(to_eval.as_slice()).unwrap();
     end_tscope!();
 
     start_tscope!("poly_eval 20var");
     let (poly, to_eval) = poly_eval_pair::<FTr>(20);
-    poly.evaluate(&to_eval.as_slice());
+    poly.evaluate(to_eval.as_slice()).unwrap();
     end_tscope!();
 
     start_tscope!("poly_eval 21var");
     let (poly, to_eval) = poly_eval_pair::<FTr>(21);
-    poly.evaluate(&to_eval.as_slice());
+    poly.evaluate(to_eval.as_slice()).unwrap();
     end_tscope!();
     end_tscope!();
 
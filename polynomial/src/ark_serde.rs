@@ -0,0 +1,151 @@
+//! Serde helpers for arkworks types
+//! values are encoded with their canonical (compressed) arkworks serialization,
+//! as a hex string for human readable formats (e.g. JSON) and raw bytes otherwise (e.g. CBOR).
+//! Use with `#[serde(with = "polynomial::ark_serde")]` on fields holding field elements.
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::de::{Error as DeError, SeqAccess, Visitor};
+use serde::ser::Error as SerError;
+use serde::{Deserializer, Serializer};
+use std::fmt;
+
+/// Serialize any canonically serializable value
+pub fn serialize<T: CanonicalSerialize, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut bytes = vec![];
+    value
+        .serialize_compressed(&mut bytes)
+        .map_err(|_| S::Error::custom("failed to serialize arkworks value"))?;
+
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&to_hex(&bytes))
+    } else {
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+/// Deserialize any canonically deserializable value
+/// the arkworks validity checks are run (e.g. field elements must be in range)
+pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    let bytes = if deserializer.is_human_readable() {
+        deserializer.deserialize_str(BytesVisitor)?
+    } else {
+        deserializer.deserialize_bytes(BytesVisitor)?
+    };
+
+    T::deserialize_compressed(bytes.as_slice())
+        .map_err(|_| D::Error::custom("invalid arkworks encoding"))
+}
+
+/// Accepts a hex string, a byte string or a sequence of bytes
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("hex string or bytes")
+    }
+
+    fn visit_str<E: DeError>(self, value: &str) -> Result<Self::Value, E> {
+        from_hex(value).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: DeError>(self, value: &[u8]) -> Result<Self::Value, E> {
+        Ok(value.to_vec())
+    }
+
+    fn visit_byte_buf<E: DeError>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(value)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = vec![];
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(value: &str) -> Result<Vec<u8>, &'static str> {
+    if !value.len().is_multiple_of(2) || !value.is_ascii() {
+        return Err("invalid hex string");
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&value[i..i + 2], 16).map_err(|_| "invalid hex string"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::multilinear::coefficient_form::CoeffMultilinearPolynomial;
+    use crate::multilinear::evaluation_form::MultiLinearPolynomial;
+    use crate::product_poly::ProductPoly;
+    use crate::univariate_poly::UnivariatePolynomial;
+    use ark_bls12_381::Fr;
+
+    #[test]
+    fn test_polynomials_json_round_trip() {
+        let mle =
+            MultiLinearPolynomial::new(2, vec![Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)])
+                .unwrap();
+        let json = serde_json::to_string(&mle).unwrap();
+        assert_eq!(
+            serde_json::from_str::<MultiLinearPolynomial<Fr>>(&json).unwrap(),
+            mle
+        );
+
+        let product = ProductPoly::new(vec![mle.clone(), mle]).unwrap();
+        let json = serde_json::to_string(&product).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ProductPoly<Fr>>(&json).unwrap(),
+            product
+        );
+
+        let coeff = CoeffMultilinearPolynomial::from_evaluation_form(&[
+            Fr::from(0),
+            Fr::from(3),
+            Fr::from(5),
+            Fr::from(9),
+        ])
+        .unwrap();
+        let json = serde_json::to_string(&coeff).unwrap();
+        assert_eq!(
+            serde_json::from_str::<CoeffMultilinearPolynomial<Fr>>(&json).unwrap(),
+            coeff
+        );
+
+        let univariate = UnivariatePolynomial::new(vec![Fr::from(5), Fr::from(2)]);
+        let json = serde_json::to_string(&univariate).unwrap();
+        assert_eq!(
+            serde_json::from_str::<UnivariatePolynomial<Fr>>(&json).unwrap(),
+            univariate
+        );
+    }
+
+    #[test]
+    fn test_invalid_encoding_rejected() {
+        #[derive(serde::Serialize)]
+        struct Evaluations(#[serde(with = "crate::ark_serde")] Vec<Fr>);
+
+        // evaluation count must be a power of 2
+        let json = serde_json::to_string(&Evaluations(vec![Fr::from(1); 3])).unwrap();
+        assert!(serde_json::from_str::<MultiLinearPolynomial<Fr>>(&json).is_err());
+
+        // product poly needs at least one factor
+        assert!(serde_json::from_str::<ProductPoly<Fr>>("[]").is_err());
+
+        // not hex
+        assert!(serde_json::from_str::<MultiLinearPolynomial<Fr>>("\"zz\"").is_err());
+    }
+}
@@ -0,0 +1,376 @@
+//! Canonical byte encodings shared by every `to_bytes` implementation
+//! the bytes produced here are absorbed into Fiat-Shamir transcripts, so two different
+//! objects must never encode to the same byte string.
+//!
+//! - lengths and counts are encoded as 8 byte integers
+//! - field elements are encoded as fixed width integers (width depends on the field)
+//! - vectors are encoded as their length followed by each element
+//! - polynomials start with a header: a length prefixed type tag followed by n_vars
+//!
+//! Integers are big endian by default (this is what the transcript absorbs),
+//! little endian output is available for consumers that expect it e.g. solidity verifiers
+//! reading arkworks style limbs. Only the byte order changes, the layout is identical.
+//!
+//! Every encoding can be streamed to a writer (`write_*`) so large polynomials can be
+//! absorbed into a transcript or written to a file without building the full byte vector.
+//! The `read_*` functions decode the same layout and reject non canonical field elements.
+
+use ark_ff::{BigInteger, PrimeField};
+use std::io::{Read, Write};
+
+/// Byte order used for lengths and field elements
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Endianness {
+    #[default]
+    Big,
+    Little,
+}
+
+/// Write a length (or count)
+pub fn write_length<W: Write>(
+    writer: &mut W,
+    length: usize,
+    endianness: Endianness,
+) -> std::io::Result<()> {
+    let length = length as u64;
+    match endianness {
+        Endianness::Big => writer.write_all(&length.to_be_bytes()),
+        Endianness::Little => writer.write_all(&length.to_le_bytes()),
+    }
+}
+
+/// Write a single field element
+pub fn write_field_element<F: PrimeField, W: Write>(
+    writer: &mut W,
+    element: &F,
+    endianness: Endianness,
+) -> std::io::Result<()> {
+    let element = element.into_bigint();
+    match endianness {
+        Endianness::Big => writer.write_all(&element.to_bytes_be()),
+        Endianness::Little => writer.write_all(&element.to_bytes_le()),
+    }
+}
+
+/// Write a length prefixed sequence of field elements
+pub fn write_field_elements<F: PrimeField, W: Write>(
+    writer: &mut W,
+    elements: &[F],
+    endianness: Endianness,
+) -> std::io::Result<()> {
+    write_length(writer, elements.len(), endianness)?;
+    for element in elements {
+        write_field_element(writer, element, endianness)?;
+    }
+    Ok(())
+}
+
+/// Write a polynomial header
+/// the tag identifies the polynomial representation
+pub fn write_polynomial_header<W: Write>(
+    writer: &mut W,
+    tag: &[u8],
+    n_vars: usize,
+    endianness: Endianness,
+) -> std::io::Result<()> {
+    write_length(writer, tag.len(), endianness)?;
+    writer.write_all(tag)?;
+    write_length(writer, n_vars, endianness)
+}
+
+/// Read a length (or count) written by `write_length`
+pub fn read_length<R: Read>(reader: &mut R, endianness: Endianness) -> std::io::Result<usize> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    let length = match endianness {
+        Endianness::Big => u64::from_be_bytes(bytes),
+        Endianness::Little => u64::from_le_bytes(bytes),
+    };
+    usize::try_from(length).map_err(|_| invalid_data("length doesn't fit in usize"))
+}
+
+/// Read a single field element written by `write_field_element`
+/// the element must be canonical (less than the modulus)
+pub fn read_field_element<F: PrimeField, R: Read>(
+    reader: &mut R,
+    endianness: Endianness,
+) -> std::io::Result<F> {
+    let mut bytes = vec![0; F::BigInt::NUM_LIMBS * 8];
+    reader.read_exact(&mut bytes)?;
+    let element = match endianness {
+        Endianness::Big => F::from_be_bytes_mod_order(&bytes),
+        Endianness::Little => F::from_le_bytes_mod_order(&bytes),
+    };
+    // reduction maps several encodings to the same element, only accept the canonical one
+    if to_bytes_with(|buffer| write_field_element(buffer, &element, endianness)) != bytes {
+        return Err(invalid_data("non canonical field element"));
+    }
+    Ok(element)
+}
+
+/// Read a length prefixed sequence of field elements written by `write_field_elements`
+pub fn read_field_elements<F: PrimeField, R: Read>(
+    reader: &mut R,
+    endianness: Endianness,
+) -> std::io::Result<Vec<F>> {
+    let length = read_length(reader, endianness)?;
+    // the length is untrusted, grow as elements are read instead of allocating it upfront
+    let mut elements = vec![];
+    for _ in 0..length {
+        elements.push(read_field_element(reader, endianness)?);
+    }
+    Ok(elements)
+}
+
+fn invalid_data(message: &'static str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// Append a length (or count) to the byte buffer
+pub fn append_length(bytes: &mut Vec<u8>, length: usize, endianness: Endianness) {
+    write_length(bytes, length, endianness).expect(VEC_WRITE)
+}
+
+/// Append a single field element to the byte buffer
+pub fn append_field_element<F: PrimeField>(
+    bytes: &mut Vec<u8>,
+    element: &F,
+    endianness: Endianness,
+) {
+    write_field_element(bytes, element, endianness).expect(VEC_WRITE)
+}
+
+/// Append a length prefixed sequence of field elements to the byte buffer
+pub fn append_field_elements<F: PrimeField>(
+    bytes: &mut Vec<u8>,
+    elements: &[F],
+    endianness: Endianness,
+) {
+    write_field_elements(bytes, elements, endianness).expect(VEC_WRITE)
+}
+
+/// Append a polynomial header to the byte buffer
+/// the tag identifies the polynomial representation
+pub fn append_polynomial_header(
+    bytes: &mut Vec<u8>,
+    tag: &[u8],
+    n_vars: usize,
+    endianness: Endianness,
+) {
+    write_polynomial_header(bytes, tag, n_vars, endianness).expect(VEC_WRITE)
+}
+
+/// Collects a streamed encoding into a byte vector
+pub fn to_bytes_with(write: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> Vec<u8> {
+    let mut bytes = vec![];
+    write(&mut bytes).expect(VEC_WRITE);
+    bytes
+}
+
+const VEC_WRITE: &str = "writing to a vec cannot fail";
+
+/// Encode a single field element (big endian)
+pub fn field_element_to_bytes<F: PrimeField>(element: &F) -> Vec<u8> {
+    let mut bytes = vec![];
+    append_field_element(&mut bytes, element, Endianness::Big);
+    bytes
+}
+
+/// Encode a length prefixed sequence of field elements (big endian)
+pub fn field_elements_to_bytes<F: PrimeField>(elements: &[F]) -> Vec<u8> {
+    let mut bytes = vec![];
+    append_field_elements(&mut bytes, elements, Endianness::Big);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        append_field_elements, append_length, append_polynomial_header, field_element_to_bytes,
+        field_elements_to_bytes, read_field_element, read_field_elements, read_length, Endianness,
+    };
+    use crate::multilinear::coefficient_form::CoeffMultilinearPolynomial;
+    use crate::multilinear::evaluation_form::MultiLinearPolynomial;
+    use crate::product_poly::ProductPoly;
+    use crate::univariate_poly::UnivariatePolynomial;
+    use crate::Polynomial;
+    use ark_bls12_381::Fr;
+    use ark_ff::{BigInteger, PrimeField};
+
+    #[test]
+    fn test_field_element_encoding() {
+        let bytes = field_element_to_bytes(&Fr::from(258));
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(&bytes[30..], &[1, 2]);
+        assert!(bytes[..30].iter().all(|byte| *byte == 0));
+
+        let bytes = field_elements_to_bytes(&[Fr::from(1), Fr::from(2)]);
+        assert_eq!(&bytes[..8], &[0, 0, 0, 0, 0, 0, 0, 2]);
+        assert_eq!(bytes.len(), 8 + 2 * 32);
+    }
+
+    #[test]
+    fn test_sequences_are_delimited() {
+        // [[a, b], []] and [[a], [b]] must not collide
+        let (a, b) = (Fr::from(1), Fr::from(2));
+
+        let mut left = vec![];
+        append_field_elements(&mut left, &[a, b], Endianness::Big);
+        append_field_elements::<Fr>(&mut left, &[], Endianness::Big);
+
+        let mut right = vec![];
+        append_field_elements(&mut right, &[a], Endianness::Big);
+        append_field_elements(&mut right, &[b], Endianness::Big);
+
+        assert_ne!(left, right);
+    }
+
+    #[test]
+    fn test_polynomial_header() {
+        let mut left = vec![];
+        append_polynomial_header(&mut left, b"mle", 2, Endianness::Big);
+        let mut right = vec![];
+        append_polynomial_header(&mut right, b"product", 2, Endianness::Big);
+        assert_ne!(left, right);
+
+        assert_eq!(left.len(), 8 + 3 + 8);
+        assert_eq!(&left[8..11], b"mle");
+    }
+
+    #[test]
+    fn test_endianness() {
+        let mut big = vec![];
+        append_field_elements(&mut big, &[Fr::from(258)], Endianness::Big);
+        let mut little = vec![];
+        append_field_elements(&mut little, &[Fr::from(258)], Endianness::Little);
+
+        assert_eq!(big.len(), little.len());
+        assert_eq!(&big[..8], &[0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(&little[..8], &[1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(&big[38..], &[1, 2]);
+        assert_eq!(&little[8..10], &[2, 1]);
+
+        let poly = MultiLinearPolynomial::new(1, vec![Fr::from(3), Fr::from(5)]).unwrap();
+        assert_eq!(
+            poly.to_bytes(),
+            poly.to_bytes_with_endianness(Endianness::Big)
+        );
+        assert_ne!(
+            poly.to_bytes(),
+            poly.to_bytes_with_endianness(Endianness::Little)
+        );
+    }
+
+    #[test]
+    fn test_coefficient_maps_do_not_collide() {
+        // {0: 1, 1: 2} vs {1: 1, 2: 2}: same coefficients, shifted indices
+        let left = CoeffMultilinearPolynomial::new_with_coefficient(
+            2,
+            [(0, Fr::from(1)), (1, Fr::from(2))].into(),
+        )
+        .unwrap();
+        let right = CoeffMultilinearPolynomial::new_with_coefficient(
+            2,
+            [(1, Fr::from(1)), (2, Fr::from(2))].into(),
+        )
+        .unwrap();
+        assert_ne!(left.to_bytes(), right.to_bytes());
+
+        // same coefficient map, different number of variables
+        let wider = CoeffMultilinearPolynomial::new_with_coefficient(
+            3,
+            [(0, Fr::from(1)), (1, Fr::from(2))].into(),
+        )
+        .unwrap();
+        assert_ne!(left.to_bytes(), wider.to_bytes());
+    }
+
+    #[test]
+    fn test_representations_do_not_collide() {
+        let (a, b) = (Fr::from(1), Fr::from(2));
+        let mle = MultiLinearPolynomial::new(1, vec![a, b]).unwrap();
+        let univariate = UnivariatePolynomial::new(vec![a, b]);
+        let product = ProductPoly::new(vec![mle.clone()]).unwrap();
+
+        let encodings = [mle.to_bytes(), univariate.to_bytes(), product.to_bytes()];
+        for i in 0..encodings.len() {
+            for j in i + 1..encodings.len() {
+                assert_ne!(encodings[i], encodings[j]);
+            }
+        }
+
+        // a product of two factors must not collide with a single factor
+        // whose evaluations happen to continue into the second factor's bytes
+        let split = ProductPoly::new(vec![mle.clone(), mle.clone()]).unwrap();
+        let joined = ProductPoly::new(vec![
+            MultiLinearPolynomial::new(2, vec![a, b, a, b]).unwrap()
+        ])
+        .unwrap();
+        assert_ne!(split.to_bytes(), joined.to_bytes());
+    }
+
+    #[test]
+    fn test_write_bytes_matches_to_bytes() {
+        let (a, b) = (Fr::from(1), Fr::from(2));
+        let mle = MultiLinearPolynomial::new(1, vec![a, b]).unwrap();
+        let univariate = UnivariatePolynomial::new(vec![a, b, Fr::from(0)]);
+        let product = ProductPoly::new(vec![mle.clone(), mle.clone()]).unwrap();
+
+        let mut streamed = vec![];
+        mle.write_bytes(&mut streamed).unwrap();
+        assert_eq!(streamed, mle.to_bytes());
+
+        let mut streamed = vec![];
+        univariate.write_bytes(&mut streamed).unwrap();
+        assert_eq!(streamed, univariate.to_bytes());
+
+        let mut streamed = vec![];
+        product
+            .write_bytes_with_endianness(&mut streamed, Endianness::Little)
+            .unwrap();
+        assert_eq!(
+            streamed,
+            product.to_bytes_with_endianness(Endianness::Little)
+        );
+    }
+
+    #[test]
+    fn test_readers_round_trip() {
+        let elements = [Fr::from(258), -Fr::from(1), Fr::from(0)];
+        for endianness in [Endianness::Big, Endianness::Little] {
+            let mut bytes = vec![];
+            append_length(&mut bytes, 7, endianness);
+            append_field_elements(&mut bytes, &elements, endianness);
+
+            let mut reader = bytes.as_slice();
+            assert_eq!(read_length(&mut reader, endianness).unwrap(), 7);
+            assert_eq!(
+                read_field_elements::<Fr, _>(&mut reader, endianness).unwrap(),
+                elements
+            );
+            assert!(reader.is_empty());
+        }
+
+        // truncated input
+        let bytes = field_elements_to_bytes(&elements);
+        assert!(
+            read_field_elements::<Fr, _>(&mut &bytes[..bytes.len() - 1], Endianness::Big).is_err()
+        );
+        // a length far beyond the input fails on the missing elements
+        let mut bytes = vec![];
+        append_length(&mut bytes, usize::MAX >> 1, Endianness::Big);
+        assert!(read_field_elements::<Fr, _>(&mut bytes.as_slice(), Endianness::Big).is_err());
+    }
+
+    #[test]
+    fn test_non_canonical_field_element_rejected() {
+        // the modulus reduces to zero, but zero has its own encoding
+        let modulus = Fr::MODULUS.to_bytes_be();
+        assert!(read_field_element::<Fr, _>(&mut modulus.as_slice(), Endianness::Big).is_err());
+        let zero = field_element_to_bytes(&Fr::from(0));
+        assert_eq!(
+            read_field_element::<Fr, _>(&mut zero.as_slice(), Endianness::Big).unwrap(),
+            Fr::from(0)
+        );
+    }
+}
@@ -0,0 +1,510 @@
+//! Radix-2 fast fourier transform over the multiplicative subgroups of the field
+//! a vector of n coefficients is evaluated at [1, w, w^2, ..., w^(n - 1)], w a primitive n-th
+//! root of unity. n has to be a power of two no larger than 2^TWO_ADICITY of the field.
+//!
+//! With the `parallel` feature, domains of at least `PARALLEL_FFT_THRESHOLD` elements use the
+//! six step algorithm: the input is treated as a matrix, the row transforms run on separate
+//! threads and the transposes keep every row transform on contiguous memory.
+//!
+//! The butterflies read their twiddle factors from a table of the powers of w, a
+//! `FftPrecomputation` keeps those tables around so repeated transforms of the same size
+//! skip rebuilding them.
+
+use ark_ff::PrimeField;
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FftError {
+    /// The input length is not a power of two
+    NotPowerOfTwo,
+    /// The field has no multiplicative subgroup of the requested size
+    UnsupportedDomainSize,
+    /// The precomputed twiddle tables are for a different domain size
+    PrecomputationSizeMismatch,
+}
+
+impl fmt::Display for FftError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FftError::NotPowerOfTwo => write!(f, "fft input length must be a power of two"),
+            FftError::UnsupportedDomainSize => {
+                write!(f, "fft domain size exceeds the two-adicity of the field")
+            }
+            FftError::PrecomputationSizeMismatch => {
+                write!(f, "fft precomputation does not match the input length")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FftError {}
+
+/// Multiplicative subgroup of the field of power of two size
+/// {1, w, w^2, ..., w^(size - 1)}
+/// holds the cached twiddle tables of its size, see `FftPrecomputation::cached`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvaluationDomain<F: PrimeField> {
+    size: usize,
+    generator: F,
+    precomputation: Arc<FftPrecomputation<F>>,
+}
+
+impl<F: PrimeField> EvaluationDomain<F> {
+    /// Instantiate the subgroup of the given size
+    pub fn new(size: usize) -> Result<Self, FftError> {
+        Ok(Self {
+            size,
+            generator: domain_root(size)?,
+            precomputation: FftPrecomputation::cached(size)?,
+        })
+    }
+
+    /// Returns the number of elements in the domain
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the generator (primitive size-th root of unity)
+    pub fn generator(&self) -> F {
+        self.generator
+    }
+
+    /// Returns the domain elements in order [1, w, w^2, ...]
+    pub fn elements(&self) -> impl Iterator<Item = F> + '_ {
+        (0..self.size).scan(F::ONE, |element, _| {
+            let current = *element;
+            *element *= self.generator;
+            Some(current)
+        })
+    }
+
+    /// Evaluates the coefficients over the domain
+    /// more coefficients than the domain size are reduced modulo X^size - 1 first
+    /// (w^size = 1 for every domain element)
+    pub fn evaluate(&self, coefficients: &[F]) -> Vec<F> {
+        let mut values = vec![F::ZERO; self.size];
+        for (i, coefficient) in coefficients.iter().enumerate() {
+            values[i % self.size] += coefficient;
+        }
+        transform(&mut values, &self.precomputation.twiddles);
+        values
+    }
+}
+
+/// Twiddle tables of a domain, built once and shared by every transform of that size
+/// twiddles[i] = w^i and inverse_twiddles[i] = w^-i for i < size / 2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FftPrecomputation<F: PrimeField> {
+    size: usize,
+    twiddles: Vec<F>,
+    inverse_twiddles: Vec<F>,
+}
+
+impl<F: PrimeField> FftPrecomputation<F> {
+    /// Builds the twiddle tables for the subgroup of the given size
+    pub fn new(size: usize) -> Result<Self, FftError> {
+        let root: F = domain_root(size)?;
+        Ok(Self {
+            size,
+            twiddles: twiddle_table(root, size),
+            inverse_twiddles: twiddle_table(
+                root.inverse().expect("roots of unity are non zero"),
+                size,
+            ),
+        })
+    }
+
+    /// Returns the process wide precomputation for the given size, built on first use
+    /// entries are keyed by field and size and kept until `clear_cache` is called
+    pub fn cached(size: usize) -> Result<Arc<Self>, FftError> {
+        let key = (TypeId::of::<F>(), size);
+        if let Some(entry) = lock_cache().get(&key) {
+            return Ok(downcast(entry.clone()));
+        }
+
+        // built without holding the lock, if another thread got there first its tables are kept
+        let precomputation: Arc<dyn Any + Send + Sync> = Arc::new(Self::new(size)?);
+        let entry = lock_cache().entry(key).or_insert(precomputation).clone();
+        Ok(downcast(entry))
+    }
+
+    /// Drops the cached precomputations of this field (of every size)
+    /// tables already handed out by `cached` stay valid
+    pub fn clear_cache() {
+        lock_cache().retain(|(field, _), _| *field != TypeId::of::<F>());
+    }
+
+    /// Returns the domain size the tables were built for
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    fn check_size(&self, size: usize) -> Result<(), FftError> {
+        if size != self.size {
+            return Err(FftError::PrecomputationSizeMismatch);
+        }
+        Ok(())
+    }
+}
+
+type PrecomputationCache = HashMap<(TypeId, usize), Arc<dyn Any + Send + Sync>>;
+
+fn lock_cache() -> MutexGuard<'static, PrecomputationCache> {
+    static CACHE: OnceLock<Mutex<PrecomputationCache>> = OnceLock::new();
+    CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn downcast<F: PrimeField>(entry: Arc<dyn Any + Send + Sync>) -> Arc<FftPrecomputation<F>> {
+    entry
+        .downcast()
+        .expect("cache entries are keyed by their field type")
+}
+
+/// Evaluates the coefficients over the subgroup of size coefficients.len()
+pub fn fft<F: PrimeField>(coefficients: &[F]) -> Result<Vec<F>, FftError> {
+    fft_with(coefficients, None)
+}
+
+/// Interpolates the evaluations over the subgroup of size evaluations.len() into coefficients
+pub fn ifft<F: PrimeField>(evaluations: &[F]) -> Result<Vec<F>, FftError> {
+    ifft_with(evaluations, None)
+}
+
+/// `fft` reading the twiddles from the precomputation when given, building them otherwise
+pub fn fft_with<F: PrimeField>(
+    coefficients: &[F],
+    precomputation: Option<&FftPrecomputation<F>>,
+) -> Result<Vec<F>, FftError> {
+    let twiddles = match precomputation {
+        Some(precomputation) => {
+            precomputation.check_size(coefficients.len())?;
+            Cow::Borrowed(precomputation.twiddles.as_slice())
+        }
+        None => Cow::Owned(twiddle_table(
+            domain_root(coefficients.len())?,
+            coefficients.len(),
+        )),
+    };
+
+    let mut values = coefficients.to_vec();
+    transform(&mut values, &twiddles);
+    Ok(values)
+}
+
+/// `ifft` reading the twiddles from the precomputation when given, building them otherwise
+pub fn ifft_with<F: PrimeField>(
+    evaluations: &[F],
+    precomputation: Option<&FftPrecomputation<F>>,
+) -> Result<Vec<F>, FftError> {
+    let inverse_twiddles = match precomputation {
+        Some(precomputation) => {
+            precomputation.check_size(evaluations.len())?;
+            Cow::Borrowed(precomputation.inverse_twiddles.as_slice())
+        }
+        None => {
+            let root: F = domain_root(evaluations.len())?;
+            Cow::Owned(twiddle_table(
+                root.inverse().expect("roots of unity are non zero"),
+                evaluations.len(),
+            ))
+        }
+    };
+
+    let mut values = evaluations.to_vec();
+    transform(&mut values, &inverse_twiddles);
+
+    let size_inverse = F::from(values.len() as u64)
+        .inverse()
+        .expect("domain size is a power of two smaller than the field characteristic");
+    values.iter_mut().for_each(|value| *value *= size_inverse);
+    Ok(values)
+}
+
+/// Zero pads the coefficients to the next power of two before running the `fft`
+pub fn fft_padded<F: PrimeField>(coefficients: &[F]) -> Result<Vec<F>, FftError> {
+    fft(&zero_pad(
+        coefficients,
+        coefficients.len().next_power_of_two(),
+    ))
+}
+
+/// Extends the values with zeros up to the given length
+pub fn zero_pad<F: PrimeField>(values: &[F], length: usize) -> Vec<F> {
+    let mut padded = values.to_vec();
+    padded.resize(length.max(values.len()), F::ZERO);
+    padded
+}
+
+/// Returns the generator of the subgroup of the given size
+fn domain_root<F: PrimeField>(size: usize) -> Result<F, FftError> {
+    if !size.is_power_of_two() {
+        return Err(FftError::NotPowerOfTwo);
+    }
+    F::get_root_of_unity(size as u64).ok_or(FftError::UnsupportedDomainSize)
+}
+
+/// Returns [1, w, w^2, ..., w^(size / 2 - 1)], the twiddles of every butterfly stage
+fn twiddle_table<F: PrimeField>(root: F, size: usize) -> Vec<F> {
+    (0..size / 2)
+        .scan(F::ONE, |power, _| {
+            let current = *power;
+            *power *= root;
+            Some(current)
+        })
+        .collect()
+}
+
+/// Domain size from which the six step transform is used (with the `parallel` feature)
+pub const PARALLEL_FFT_THRESHOLD: usize = 1 << 20;
+
+/// In place transform, picks the implementation based on the domain size
+/// twiddles is the twiddle table of a domain of size values.len()
+fn transform<F: PrimeField>(values: &mut [F], twiddles: &[F]) {
+    #[cfg(feature = "parallel")]
+    if values.len() >= PARALLEL_FFT_THRESHOLD {
+        six_step_transform(values, twiddles);
+        return;
+    }
+
+    radix2_transform(values, twiddles);
+}
+
+/// In place iterative Cooley-Tukey transform
+/// values.len() must be a power of two dividing the size of the twiddle table's domain,
+/// the root of the sub domain is w^(table size / values.len()) so a stage reads every
+/// (table size / (2 * half))-th twiddle
+fn radix2_transform<F: PrimeField>(values: &mut [F], twiddles: &[F]) {
+    let n = values.len();
+    let log_n = n.trailing_zeros();
+    let table_size = 2 * twiddles.len();
+
+    // bit reversal permutation, so the butterflies can run in place
+    for i in 0..n {
+        let j = (i.reverse_bits() >> (usize::BITS - log_n.max(1))) & (n - 1);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let mut half = 1;
+    while half < n {
+        // twiddles of a primitive root of unity of order 2 * half
+        let stride = table_size / (2 * half);
+        for chunk in values.chunks_mut(2 * half) {
+            for i in 0..half {
+                let odd = chunk[i + half] * twiddles[i * stride];
+                chunk[i + half] = chunk[i] - odd;
+                chunk[i] += odd;
+            }
+        }
+        half *= 2;
+    }
+}
+
+/// Six step transform, n = rows * columns
+/// the input is read as a rows x columns matrix (row major), X[k1 + rows * k2] is
+/// sum_{j2} w^(rows j2 k2) w^(j2 k1) sum_{j1} x[columns j1 + j2] w^(columns j1 k1)
+/// 1. transpose, 2. length rows transform of every row (the inner sum)
+/// 3. multiply by the twiddles w^(j2 k1), 4. transpose
+/// 5. length columns transform of every row (the outer sum), 6. transpose into output order
+#[cfg(feature = "parallel")]
+fn six_step_transform<F: PrimeField>(values: &mut [F], twiddles: &[F]) {
+    use rayon::prelude::*;
+
+    let n = values.len();
+    let log_n = n.trailing_zeros();
+    let rows = 1 << (log_n / 2);
+    let columns = n / rows;
+
+    // columns x rows, row j2 holds x[columns * j1 + j2] for every j1
+    let mut matrix = transpose(values, rows, columns);
+    matrix
+        .par_chunks_mut(rows)
+        .enumerate()
+        .for_each(|(j2, row)| {
+            radix2_transform(row, twiddles);
+            for (k1, value) in row.iter_mut().enumerate() {
+                *value *= twiddle(twiddles, j2 * k1);
+            }
+        });
+
+    // rows x columns, row k1 holds the twiddled inner sums for every j2
+    let mut matrix = transpose(&matrix, columns, rows);
+    matrix
+        .par_chunks_mut(columns)
+        .for_each(|row| radix2_transform(row, twiddles));
+
+    // row k1, column k2 holds X[k1 + rows * k2]
+    values.copy_from_slice(&transpose(&matrix, rows, columns));
+}
+
+/// w^exponent from the twiddle table, w^(n / 2) = -1 covers the upper half
+#[cfg(feature = "parallel")]
+fn twiddle<F: PrimeField>(twiddles: &[F], exponent: usize) -> F {
+    let half = twiddles.len();
+    let exponent = exponent % (2 * half);
+    if exponent < half {
+        twiddles[exponent]
+    } else {
+        -twiddles[exponent - half]
+    }
+}
+
+/// Transposes a rows x columns row major matrix
+/// works on square tiles so reads and writes both stay within a few cache lines
+#[cfg(feature = "parallel")]
+fn transpose<F: PrimeField>(values: &[F], rows: usize, columns: usize) -> Vec<F> {
+    use rayon::prelude::*;
+
+    const TILE: usize = 16;
+    let mut result = vec![F::ZERO; values.len()];
+    // each task fills TILE rows of the output (TILE columns of the input)
+    result
+        .par_chunks_mut(TILE * rows)
+        .enumerate()
+        .for_each(|(tile_index, output)| {
+            let column_start = tile_index * TILE;
+            let column_end = (column_start + TILE).min(columns);
+            for row_start in (0..rows).step_by(TILE) {
+                for row in row_start..(row_start + TILE).min(rows) {
+                    for column in column_start..column_end {
+                        output[(column - column_start) * rows + row] =
+                            values[row * columns + column];
+                    }
+                }
+            }
+        });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        fft, fft_padded, fft_with, ifft, ifft_with, EvaluationDomain, FftError, FftPrecomputation,
+    };
+    use crate::test_fields::F17 as Fq;
+    use crate::univariate_poly::UnivariatePolynomial;
+    use ark_bls12_381::Fr;
+    use ark_ff::{FftField, Field, UniformRand};
+
+    #[test]
+    fn test_fft_matches_naive_evaluation() {
+        let mut rng = ark_std::test_rng();
+        for size in [1, 2, 4, 16] {
+            let coefficients = (0..size).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+            let poly = UnivariatePolynomial::new(coefficients.clone());
+            let root = Fr::get_root_of_unity(size as u64).unwrap();
+
+            let evaluations = fft(&coefficients).unwrap();
+            for (i, evaluation) in evaluations.iter().enumerate() {
+                assert_eq!(*evaluation, poly.evaluate(&root.pow([i as u64])));
+            }
+            assert_eq!(ifft(&evaluations).unwrap(), coefficients);
+        }
+    }
+
+    #[test]
+    fn test_fft_errors() {
+        let values = vec![Fq::from(1); 3];
+        assert_eq!(fft(&values), Err(FftError::NotPowerOfTwo));
+        assert_eq!(ifft(&values), Err(FftError::NotPowerOfTwo));
+        assert_eq!(fft::<Fq>(&[]), Err(FftError::NotPowerOfTwo));
+
+        // 17 - 1 = 2^4, there is no subgroup of size 32
+        assert!(fft(&[Fq::from(1); 16]).is_ok());
+        assert_eq!(
+            fft(&[Fq::from(1); 32]),
+            Err(FftError::UnsupportedDomainSize)
+        );
+    }
+
+    #[test]
+    fn test_fft_padded() {
+        let coefficients = vec![Fq::from(3), Fq::from(2), Fq::from(5)];
+        let evaluations = fft_padded(&coefficients).unwrap();
+        assert_eq!(evaluations.len(), 4);
+        assert_eq!(
+            ifft(&evaluations).unwrap(),
+            vec![Fq::from(3), Fq::from(2), Fq::from(5), Fq::from(0)]
+        );
+    }
+
+    #[test]
+    fn test_fft_precomputation() {
+        let mut rng = ark_std::test_rng();
+        for size in [1, 2, 8, 64] {
+            let precomputation = FftPrecomputation::<Fr>::cached(size).unwrap();
+            assert_eq!(precomputation.size(), size);
+
+            let coefficients = (0..size).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+            let evaluations = fft_with(&coefficients, Some(&precomputation)).unwrap();
+            assert_eq!(evaluations, fft(&coefficients).unwrap());
+            assert_eq!(
+                ifft_with(&evaluations, Some(&precomputation)).unwrap(),
+                coefficients
+            );
+        }
+
+        // the cache hands out the same tables per field and size
+        let first = FftPrecomputation::<Fr>::cached(8).unwrap();
+        let second = FftPrecomputation::<Fr>::cached(8).unwrap();
+        assert!(std::sync::Arc::ptr_eq(&first, &second));
+        let small_field = FftPrecomputation::<Fq>::cached(8).unwrap();
+        assert_eq!(small_field.size(), 8);
+        let domain = EvaluationDomain::<Fr>::new(8).unwrap();
+        assert!(std::sync::Arc::ptr_eq(&domain.precomputation, &first));
+
+        // clearing rebuilds the tables of that field only
+        FftPrecomputation::<Fr>::clear_cache();
+        let rebuilt = FftPrecomputation::<Fr>::cached(8).unwrap();
+        assert!(!std::sync::Arc::ptr_eq(&first, &rebuilt));
+        assert_eq!(rebuilt, first);
+        assert!(std::sync::Arc::ptr_eq(
+            &small_field,
+            &FftPrecomputation::<Fq>::cached(8).unwrap()
+        ));
+        assert_eq!(
+            FftPrecomputation::<Fq>::cached(32),
+            Err(FftError::UnsupportedDomainSize)
+        );
+
+        let values = vec![Fr::from(1); 4];
+        assert_eq!(
+            fft_with(&values, Some(&first)),
+            Err(FftError::PrecomputationSizeMismatch)
+        );
+        assert_eq!(
+            ifft_with(&values, Some(&first)),
+            Err(FftError::PrecomputationSizeMismatch)
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_six_step_matches_radix2() {
+        use super::{radix2_transform, six_step_transform, twiddle_table};
+
+        let mut rng = ark_std::test_rng();
+        // odd and even log sizes (rectangular and square matrices)
+        for log_size in [1, 2, 5, 6, 9] {
+            let size = 1 << log_size;
+            let root = Fr::get_root_of_unity(size as u64).unwrap();
+            let values = (0..size).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+
+            let twiddles = twiddle_table(root, size);
+
+            let mut expected = values.clone();
+            radix2_transform(&mut expected, &twiddles);
+            let mut actual = values;
+            six_step_transform(&mut actual, &twiddles);
+            assert_eq!(actual, expected);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Sums over the boolean hypercube
+//! sum_{x in {0, 1}^n} P(x) for any polynomial that can be evaluated at a hypercube index
+
+use ark_ff::PrimeField;
+use std::ops::ControlFlow;
+
+/// How `HypercubeSum::sum_over_boolean_hypercube_with` walks the hypercube
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SumMode {
+    /// One index after the other on the calling thread
+    #[default]
+    Serial,
+    /// Splits the indices across the rayon thread pool
+    /// same as `Serial` when the `parallel` feature is off
+    Parallel,
+}
+
+/// Polynomials that can be summed over the boolean hypercube
+/// variable 0 is the most significant bit of a hypercube index
+pub trait HypercubeSum<F: PrimeField>: Sync {
+    /// Returns the number of hypercube points (2^n for n free variables)
+    fn hypercube_size(&self) -> usize;
+
+    /// Returns the evaluation at the hypercube point with the given index
+    /// index must be less than `hypercube_size`
+    fn hypercube_evaluation(&self, index: usize) -> F;
+
+    /// sum_{x in {0, 1}^n} P(x)
+    fn sum_over_boolean_hypercube(&self) -> F {
+        self.sum_over_boolean_hypercube_with(SumMode::Serial)
+    }
+
+    /// sum_{x in {0, 1}^n} P(x), walking the hypercube as described by mode
+    fn sum_over_boolean_hypercube_with(&self, mode: SumMode) -> F {
+        match mode {
+            #[cfg(feature = "parallel")]
+            SumMode::Parallel => {
+                use rayon::prelude::*;
+
+                (0..self.hypercube_size())
+                    .into_par_iter()
+                    .map(|index| self.hypercube_evaluation(index))
+                    .sum()
+            }
+            _ => (0..self.hypercube_size())
+                .map(|index| self.hypercube_evaluation(index))
+                .sum(),
+        }
+    }
+
+    /// Sums in index order until `stop(index, evaluation)` returns true
+    /// returns Break((index, sum of the evaluations before index)) if it stopped early
+    /// Continue(full sum) otherwise
+    fn sum_over_boolean_hypercube_until(
+        &self,
+        mut stop: impl FnMut(usize, &F) -> bool,
+    ) -> ControlFlow<(usize, F), F> {
+        let mut sum = F::zero();
+        for index in 0..self.hypercube_size() {
+            let evaluation = self.hypercube_evaluation(index);
+            if stop(index, &evaluation) {
+                return ControlFlow::Break((index, sum));
+            }
+            sum += evaluation;
+        }
+        ControlFlow::Continue(sum)
+    }
+}
+
+/// Evaluation table, entry i is the evaluation at hypercube index i
+impl<F: PrimeField> HypercubeSum<F> for [F] {
+    fn hypercube_size(&self) -> usize {
+        self.len()
+    }
+
+    fn hypercube_evaluation(&self, index: usize) -> F {
+        self[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HypercubeSum, SumMode};
+    use crate::multilinear::coefficient_form::CoeffMultilinearPolynomial;
+    use crate::multilinear::evaluation_form::MultiLinearPolynomial;
+    use crate::oracle_poly::OraclePoly;
+    use crate::product_poly::ProductPoly;
+    use crate::sum_poly::SumPoly;
+    use crate::test_fields::random_mle;
+    use ark_bls12_381::Fr;
+    use ark_ff::{Field, One, UniformRand, Zero};
+    use std::ops::ControlFlow;
+
+    fn bits(index: usize, n_vars: usize) -> Vec<Fr> {
+        (0..n_vars)
+            .rev()
+            .map(|bit| Fr::from(((index >> bit) & 1) as u64))
+            .collect()
+    }
+
+    /// Checks every mode against a naive loop over evaluate
+    fn assert_sums_match(
+        poly: &(impl HypercubeSum<Fr> + ?Sized),
+        n_vars: usize,
+        evaluate: impl Fn(&[Fr]) -> Fr,
+    ) {
+        let naive = (0..1 << n_vars)
+            .map(|index| evaluate(&bits(index, n_vars)))
+            .sum::<Fr>();
+
+        assert_eq!(poly.hypercube_size(), 1 << n_vars);
+        assert_eq!(poly.sum_over_boolean_hypercube(), naive);
+        assert_eq!(poly.sum_over_boolean_hypercube_with(SumMode::Serial), naive);
+        assert_eq!(
+            poly.sum_over_boolean_hypercube_with(SumMode::Parallel),
+            naive
+        );
+        assert_eq!(
+            poly.sum_over_boolean_hypercube_until(|_, _| false),
+            ControlFlow::Continue(naive)
+        );
+    }
+
+    #[test]
+    fn test_sums_match_naive_loop() {
+        let mut rng = ark_std::test_rng();
+        for n_vars in 0..6 {
+            for _ in 0..4 {
+                let mle = random_mle::<Fr, _>(n_vars, &mut rng);
+                assert_sums_match(&mle, n_vars, |point| mle.evaluate(point).unwrap());
+                assert_sums_match(mle.evaluation_slice(), n_vars, |point| {
+                    mle.evaluate(point).unwrap()
+                });
+
+                let coeff =
+                    CoeffMultilinearPolynomial::from_evaluation_form(mle.evaluation_slice())
+                        .unwrap();
+                assert_sums_match(&coeff, n_vars, |point| mle.evaluate(point).unwrap());
+
+                let product = ProductPoly::new(vec![
+                    mle.clone(),
+                    random_mle(n_vars, &mut rng),
+                    random_mle(n_vars, &mut rng),
+                ])
+                .unwrap();
+                assert_sums_match(&product, n_vars, |point| product.evaluate(point).unwrap());
+
+                let sum = SumPoly::new(vec![
+                    (Fr::rand(&mut rng), product.clone()),
+                    (
+                        Fr::rand(&mut rng),
+                        ProductPoly::new(vec![mle.clone()]).unwrap(),
+                    ),
+                ])
+                .unwrap();
+                assert_sums_match(&sum, n_vars, |point| sum.evaluate(point).unwrap());
+
+                let oracle_mle = mle.clone();
+                let oracle = OraclePoly::new(n_vars, 2, move |point: &[Fr]| {
+                    oracle_mle.evaluate(point).unwrap().square()
+                });
+                assert_sums_match(&oracle, n_vars, |point| {
+                    mle.evaluate(point).unwrap().square()
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn test_oracle_sum_skips_fixed_variables() {
+        let mut rng = ark_std::test_rng();
+        let mle = random_mle::<Fr, _>(4, &mut rng);
+        let oracle_mle = mle.clone();
+        let mut oracle = OraclePoly::new(4, 1, move |point: &[Fr]| {
+            oracle_mle.evaluate(point).unwrap()
+        });
+        let r = Fr::rand(&mut rng);
+        oracle.fold_in_place(&r).unwrap();
+
+        let folded = mle.fold(&r).unwrap();
+        assert_eq!(
+            oracle.sum_over_boolean_hypercube(),
+            folded.sum_over_boolean_hypercube()
+        );
+    }
+
+    #[test]
+    fn test_sum_until() {
+        // evaluations 1, 2, 0, 4, 0, 6, 7, 8
+        let evaluations = [1, 2, 0, 4, 0, 6, 7, 8].map(Fr::from);
+        let mle = MultiLinearPolynomial::new(3, evaluations.to_vec()).unwrap();
+
+        // stops at the first zero, the sum excludes it
+        assert_eq!(
+            mle.sum_over_boolean_hypercube_until(|_, value| value.is_zero()),
+            ControlFlow::Break((2, Fr::from(3)))
+        );
+        assert_eq!(
+            mle.sum_over_boolean_hypercube_until(|index, _| index == 6),
+            ControlFlow::Break((6, Fr::from(13)))
+        );
+        assert_eq!(
+            mle.sum_over_boolean_hypercube_until(|_, value| *value == Fr::from(9)),
+            ControlFlow::Continue(Fr::from(28))
+        );
+
+        // no variables, a single point
+        let constant = MultiLinearPolynomial::new(0, vec![Fr::one()]).unwrap();
+        assert_eq!(
+            constant.sum_over_boolean_hypercube_until(|_, value| value.is_one()),
+            ControlFlow::Break((0, Fr::zero()))
+        );
+    }
+}
@@ -1,9 +1,20 @@
 use ark_ff::PrimeField;
 
+use self::encoding::Endianness;
 use self::univariate_poly::UnivariatePolynomial;
+use std::io::Write;
 
+#[cfg(feature = "serde")]
+pub mod ark_serde;
+pub mod encoding;
+pub mod fft;
+pub mod hypercube_sum;
 pub mod multilinear;
+pub mod oracle_poly;
 pub mod product_poly;
+pub mod sum_poly;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_fields;
 pub mod univariate_poly;
 
 // TODO: get rid of this trait
@@ -29,9 +40,48 @@ pub trait Polynomial<F: PrimeField>: Clone {
 
     /// Converts the polynomial to a sequence of bytes
     /// mostly used for fiat-shamir
-    fn to_bytes(&self) -> Vec<u8>;
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with_endianness(Endianness::Big)
+    }
+
+    /// Converts the polynomial to a sequence of bytes with the given byte order
+    fn to_bytes_with_endianness(&self, endianness: Endianness) -> Vec<u8> {
+        encoding::to_bytes_with(|bytes| self.write_bytes_with_endianness(bytes, endianness))
+    }
+
+    /// Streams the `to_bytes` encoding to a writer
+    fn write_bytes<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.write_bytes_with_endianness(writer, Endianness::Big)
+    }
+
+    /// Streams the `to_bytes_with_endianness` encoding to a writer
+    fn write_bytes_with_endianness<W: Write>(
+        &self,
+        writer: &mut W,
+        endianness: Endianness,
+    ) -> std::io::Result<()>;
 
     // TODO: this might be removed (doesn't have to be a strict requirement)
     /// Attempt conversion to univariate polynomial
     fn to_univariate(&self) -> Result<UnivariatePolynomial<F>, &'static str>;
 }
+
+/// Polynomial the sumcheck prover can run over
+/// each round sends the round polynomial then fixes the first free variable to the challenge
+pub trait SumcheckPolynomial<F: PrimeField>: Clone {
+    /// Returns the number of free variables
+    fn n_vars(&self) -> usize;
+
+    /// Returns the max degree of any single variable
+    fn max_variable_degree(&self) -> usize;
+
+    /// Evaluations of g(X) = sum_{x in {0, 1}^(n - 1)} P(X, x) at 0, 1, ..., max_variable_degree
+    fn round_evaluations(&self) -> Result<Vec<F>, &'static str>;
+
+    /// Fixes the first free variable to r
+    fn fold_in_place(&mut self, r: &F) -> Result<(), &'static str>;
+
+    /// Streams the encoding absorbed when a proof is bound to the full poly
+    /// polys without an encoding (e.g. oracles) return an error, bind a commitment instead
+    fn write_transcript_bytes<W: Write>(&self, writer: &mut W) -> std::io::Result<()>;
+}
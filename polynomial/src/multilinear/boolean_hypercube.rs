@@ -1,4 +1,3 @@
-use crate::multilinear::coefficient_form::binary_string;
 use ark_ff::PrimeField;
 use std::marker::PhantomData;
 
@@ -31,11 +30,15 @@ impl<F: PrimeField> Iterator for BooleanHyperCube<F> {
             return None;
         }
 
-        // convert the current index to binary value of the given length
-        let index_as_binary = binary_string(self.current_point, self.bit_size);
-        let point = index_as_binary
-            .chars()
-            .map(|a| if a == '1' { F::one() } else { F::zero() })
+        // the bits of the current index, most significant first
+        let point = (0..self.bit_size)
+            .map(|i| {
+                if (self.current_point >> (self.bit_size - 1 - i)) & 1 == 1 {
+                    F::one()
+                } else {
+                    F::zero()
+                }
+            })
             .collect::<Vec<F>>();
 
         self.current_point += 1;
@@ -47,15 +50,10 @@ impl<F: PrimeField> Iterator for BooleanHyperCube<F> {
 #[cfg(test)]
 mod tests {
     use crate::multilinear::boolean_hypercube::BooleanHyperCube;
-    use ark_ff::{Fp64, MontBackend, MontConfig, One, Zero};
+    use crate::test_fields::F17 as Fq;
+    use ark_ff::{One, Zero};
     use std::iter::Iterator;
 
-    #[derive(MontConfig)]
-    #[modulus = "17"]
-    #[generator = "3"]
-    struct FqConfig;
-    type Fq = Fp64<MontBackend<FqConfig, 1>>;
-
     #[test]
     fn test_boolean_hypercube_iteration() {
         let mut two_bit_iterator = BooleanHyperCube::<Fq>::new(2);
@@ -1,9 +1,12 @@
+use crate::encoding::{self, Endianness};
+use crate::hypercube_sum::HypercubeSum;
 use crate::multilinear::boolean_hypercube::BooleanHyperCube;
 use crate::univariate_poly::UnivariatePolynomial;
 use crate::Polynomial;
-use ark_ff::{BigInteger, PrimeField};
+use ark_ff::PrimeField;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use std::collections::BTreeMap;
+use std::io::Write;
 use std::ops::{Add, Mul};
 
 /// Polynomial term represents a monomial
@@ -128,14 +131,19 @@ impl<F: PrimeField> Polynomial<F> for CoeffMultilinearPolynomial<F> {
     }
 
     /// Serialize the multilinear polynomial
-    fn to_bytes(&self) -> Vec<u8> {
-        let mut result = vec![];
-        result.extend(self.n_vars.to_be_bytes());
-        for (var_id, coeff) in self.coefficients() {
-            result.extend(var_id.to_be_bytes());
-            result.extend(coeff.into_bigint().to_bytes_be());
+    /// the coefficient map is encoded as a count followed by (index, coefficient) pairs
+    fn write_bytes_with_endianness<W: Write>(
+        &self,
+        writer: &mut W,
+        endianness: Endianness,
+    ) -> std::io::Result<()> {
+        encoding::write_polynomial_header(writer, b"coeff_mle", self.n_vars as usize, endianness)?;
+        encoding::write_length(writer, self.coefficients.len(), endianness)?;
+        for (var_id, coeff) in &self.coefficients {
+            encoding::write_length(writer, *var_id, endianness)?;
+            encoding::write_field_element(writer, coeff, endianness)?;
         }
-        result
+        Ok(())
     }
 
     /// Convert a multilinear polynomial with 1 variable to a univariate poly
@@ -197,7 +205,14 @@ impl<F: PrimeField> CoeffMultilinearPolynomial<F> {
     }
 
     /// Interpolate a set of values over the boolean hypercube
+    /// missing values (up to the next power of two) are taken as zero
     pub fn interpolate(values: &[F]) -> Self {
+        Self::interpolate_with(values, InterpolationStrategy::default())
+    }
+
+    /// Interpolate a set of values over the boolean hypercube with the given strategy
+    /// both strategies return the same polynomial
+    pub fn interpolate_with(values: &[F], strategy: InterpolationStrategy) -> Self {
         // if no points to interpolate, return zero poly
         if values.is_empty() {
             return Self::new(0, vec![]).unwrap();
@@ -205,6 +220,24 @@ impl<F: PrimeField> CoeffMultilinearPolynomial<F> {
 
         let num_of_variables = bit_count_for_n_elem(values.len());
 
+        match strategy {
+            InterpolationStrategy::Mobius => {
+                let padded = values
+                    .iter()
+                    .copied()
+                    .chain(std::iter::repeat(F::zero()))
+                    .take(1 << num_of_variables)
+                    .collect::<Vec<_>>();
+                Self::from_evaluation_form(&padded).expect("padded to a power of two evaluations")
+            }
+            InterpolationStrategy::LagrangeBasis => {
+                Self::interpolate_lagrange(values, num_of_variables)
+            }
+        }
+    }
+
+    /// Sums value . basis poly one value at a time
+    fn interpolate_lagrange(values: &[F], num_of_variables: usize) -> Self {
         let mut result = Self::additive_identity();
         for (i, value) in values.iter().enumerate() {
             let poly = Self::lagrange_basis_poly(i, num_of_variables).scalar_multiply(value);
@@ -216,24 +249,56 @@ impl<F: PrimeField> CoeffMultilinearPolynomial<F> {
     /// Generate a checker polynomial for a boolean value that
     /// outputs 1 if the boolean values match, 0 otherwise
     fn lagrange_basis_poly(index: usize, num_of_vars: usize) -> Self {
-        let binary_value = binary_string(index, num_of_vars);
-        Self::bit_string_checker(binary_value)
+        Self::index_checker(index, num_of_vars)
+    }
+
+    /// Constructs the n-var multilinear polynomial that evaluates to 1 at the hypercube
+    /// point with the given index (variable 0 is the most significant bit) and 0 elsewhere
+    /// prod_{i: bit 1} x_i . prod_{i: bit 0} (1 - x_i), expanded directly into coefficients:
+    /// every subset T of the zero bits contributes (-1)^|T| to the monomial (one bits + T)
+    pub fn index_checker(index: usize, n_vars: usize) -> Self {
+        // coefficient keys hold variable i at bit i
+        let (mut ones, mut zeros) = (0_usize, 0_usize);
+        for i in 0..n_vars {
+            if (index >> (n_vars - 1 - i)) & 1 == 1 {
+                ones |= 1 << i;
+            } else {
+                zeros |= 1 << i;
+            }
+        }
+
+        let mut coefficients = BTreeMap::new();
+        // walk the submasks of zeros, from zeros down to 0
+        let mut subset = zeros;
+        loop {
+            let sign = if subset.count_ones() % 2 == 0 {
+                F::one()
+            } else {
+                -F::one()
+            };
+            coefficients.insert(ones | subset, sign);
+            if subset == 0 {
+                break;
+            }
+            subset = (subset - 1) & zeros;
+        }
+
+        Self {
+            n_vars: n_vars as u32,
+            coefficients,
+        }
     }
 
     /// Given some bit string of len n e.g. 0100
     /// constructs an n-var multilinear polynomial that evaluates to 1
     /// when the given bit string is given as input
     /// and evaluates to 0 for another bit string
+    /// string form of `index_checker`, kept for debugging
     pub fn bit_string_checker(bit_string: String) -> Self {
-        bit_string
+        let index = bit_string
             .chars()
-            .fold(Self::multiplicative_identity(), |acc, char| {
-                if char == '1' {
-                    &acc * &Self::check_one()
-                } else {
-                    &acc * &Self::check_zero()
-                }
-            })
+            .fold(0, |index, char| (index << 1) | (char == '1') as usize);
+        Self::index_checker(index, bit_string.len())
     }
 
     /// Determines which variables are represented in the polynomial
@@ -253,6 +318,7 @@ impl<F: PrimeField> CoeffMultilinearPolynomial<F> {
     }
 
     /// Multilinear polynomial to check if a variable in the boolean space is 0
+    #[cfg(test)]
     fn check_zero() -> Self {
         // p = 1 - a
         Self::new(
@@ -263,6 +329,7 @@ impl<F: PrimeField> CoeffMultilinearPolynomial<F> {
     }
 
     /// Multilinear polynomial to check if a variable in the boolean space is 1
+    #[cfg(test)]
     fn check_one() -> Self {
         // p = a
         Self::new(1, vec![(F::one(), vec![true])]).unwrap()
@@ -332,6 +399,7 @@ impl<F: PrimeField> CoeffMultilinearPolynomial<F> {
     }
 
     /// Multiplicative identity poly
+    #[cfg(test)]
     fn multiplicative_identity() -> Self {
         Self::new(0, vec![(F::one(), vec![])]).unwrap()
     }
@@ -345,6 +413,81 @@ impl<F: PrimeField> CoeffMultilinearPolynomial<F> {
         }
         evaluations
     }
+
+    /// Converts a polynomial in evaluation form to co-efficient form
+    /// inverse of `to_evaluation_form`, the evaluations are over the boolean hypercube
+    /// with the first variable as the most significant bit of the evaluation index
+    pub fn from_evaluation_form(evaluations: &[F]) -> Result<Self, &'static str> {
+        if !evaluations.len().is_power_of_two() {
+            return Err("evaluation vec len should be a power of 2");
+        }
+
+        let n_vars = evaluations.len().trailing_zeros();
+
+        // mobius transform over the hypercube
+        // for each variable, subtract the evaluation with that variable set to 0
+        // from the evaluation with the variable set to 1
+        // after all variables, entry i holds the coefficient of the monomial given by the bits of i
+        // e.g. f(a, b) evals [f(00), f(01), f(10), f(11)]
+        // becomes [f(00), f(01) - f(00), f(10) - f(00), f(11) - f(10) - f(01) + f(00)]
+        let mut values = evaluations.to_vec();
+        for bit in 0..n_vars {
+            let step = 1 << bit;
+            for i in 0..values.len() {
+                if i & step != 0 {
+                    let without_var = values[i ^ step];
+                    values[i] -= without_var;
+                }
+            }
+        }
+
+        // evaluation index has the first variable as the most significant bit
+        // coefficient index has the first variable as the least significant bit
+        // so the index bits need to be reversed
+        let coefficients = values
+            .into_iter()
+            .enumerate()
+            .filter(|(_, coeff)| !coeff.is_zero())
+            .map(|(i, coeff)| (reverse_bits(i, n_vars as usize), coeff))
+            .collect();
+
+        Self::new_with_coefficient(n_vars, coefficients)
+    }
+}
+
+/// How `CoeffMultilinearPolynomial::interpolate_with` computes the coefficients
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InterpolationStrategy {
+    /// Mobius transform over a dense table, O(n 2^n) field operations
+    /// holds all 2^n values at once (plus the coefficient map)
+    #[default]
+    Mobius,
+    /// Adds the Lagrange basis poly of each value into the result one at a time
+    /// no dense table, memory follows the number of non zero coefficients, but each
+    /// basis poly has up to 2^n terms, O(3^n) terms in total (only for small n)
+    LagrangeBasis,
+}
+
+/// Reverses the first bit_count bits of a number
+/// e.g. reverse_bits(0b001, 3) = 0b100
+fn reverse_bits(value: usize, bit_count: usize) -> usize {
+    (0..bit_count).fold(0, |acc, bit| (acc << 1) | ((value >> bit) & 1))
+}
+
+impl<F: PrimeField> HypercubeSum<F> for CoeffMultilinearPolynomial<F> {
+    fn hypercube_size(&self) -> usize {
+        1 << self.n_vars
+    }
+
+    /// Sum of the coefficients whose variables are all set at the index
+    fn hypercube_evaluation(&self, index: usize) -> F {
+        let present = reverse_bits(index, self.n_vars as usize);
+        self.coefficients
+            .iter()
+            .filter(|(term, _)| **term & !present == 0)
+            .map(|(_, coefficient)| *coefficient)
+            .sum()
+    }
 }
 
 impl<F: PrimeField> Add for &CoeffMultilinearPolynomial<F> {
@@ -429,20 +572,11 @@ fn selector_to_index(selector: &[bool]) -> usize {
     sum
 }
 
-/// Convert a number to a vec of bool
+/// Convert a number to a vec of bool (least significant bit first)
 pub fn selector_from_usize(value: usize, exact_size: usize) -> Vec<bool> {
-    let binary_value = format!("{:b}", value);
-    let mut result = vec![];
-    for char in binary_value.chars() {
-        if char == '1' {
-            result.push(true)
-        } else {
-            result.push(false)
-        }
-    }
-    result.reverse();
-    result.resize(exact_size, false);
-    result
+    (0..exact_size)
+        .map(|i| i < usize::BITS as usize && (value >> i) & 1 == 1)
+        .collect()
 }
 
 // TODO: move to until file
@@ -458,6 +592,7 @@ pub fn selector_from_position(size: usize, position: usize) -> Result<Vec<bool>,
 }
 
 /// Convert a number to a binary string of a given size
+/// for debugging output, the polynomial constructions work on the index directly
 pub fn binary_string(index: usize, bit_count: usize) -> String {
     let binary = format!("{:b}", index);
     "0".repeat(bit_count.saturating_sub(binary.len())) + &binary
@@ -522,27 +657,37 @@ pub fn bit_count_for_n_elem(size: usize) -> usize {
     format!("{:b}", size - 1).len()
 }
 
+#[cfg(feature = "serde")]
+impl<F: PrimeField> serde::Serialize for CoeffMultilinearPolynomial<F> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::ark_serde::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, F: PrimeField> serde::Deserialize<'de> for CoeffMultilinearPolynomial<F> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let poly: Self = crate::ark_serde::deserialize(deserializer)?;
+        // ensure the coefficient map fits the number of variables
+        Self::new_with_coefficient(poly.n_vars, poly.coefficients).map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::multilinear::coefficient_form::{
-        mapping_instruction_from_variable_presence, selector_to_index, to_power_of_two,
-        CoeffMultilinearPolynomial,
+        binary_string, mapping_instruction_from_variable_presence, selector_to_index,
+        to_power_of_two, CoeffMultilinearPolynomial, InterpolationStrategy,
     };
+    use crate::multilinear::evaluation_form::MultiLinearPolynomial;
+    use crate::test_fields::{field_vec, F17 as Fq};
     use crate::Polynomial;
-    use ark_ff::{Fp64, MontBackend, MontConfig, One, Zero};
+    use ark_ff::{One, Zero};
     use std::collections::BTreeMap;
     use std::ops::Neg;
 
-    #[derive(MontConfig)]
-    #[modulus = "17"]
-    #[generator = "3"]
-    struct FqConfig;
-    type Fq = Fp64<MontBackend<FqConfig, 1>>;
-
-    // TODO: move this functionality into the polynomial struct
-    // TODO: make this generic over field
     fn fq_from_vec(values: Vec<i64>) -> Vec<Fq> {
-        values.into_iter().map(Fq::from).collect()
+        field_vec(&values)
     }
 
     fn fq_map_from_vec(values: Vec<i64>) -> BTreeMap<usize, Fq> {
@@ -616,10 +761,7 @@ mod tests {
     #[test]
     fn test_polynomial_instantiation_invalid_variables() {
         // polynomial expects 3 variables by passed a term with just 2 variables
-        assert_eq!(
-            CoeffMultilinearPolynomial::new(3, vec![(Fq::from(2), vec![true, true])]).is_err(),
-            true
-        );
+        assert!(CoeffMultilinearPolynomial::new(3, vec![(Fq::from(2), vec![true, true])]).is_err());
     }
 
     #[test]
@@ -648,19 +790,16 @@ mod tests {
         //  d = [8, 9, 10, 11, 12, 13, 14, 15]
 
         // you cannot get indexes for const or multiple variables
-        assert_eq!(
-            CoeffMultilinearPolynomial::<Fq>::get_variable_indexes(
-                4,
-                &[false, false, false, false]
-            )
-            .is_err(),
-            true
-        );
-        assert_eq!(
-            CoeffMultilinearPolynomial::<Fq>::get_variable_indexes(4, &[true, false, true, false])
-                .is_err(),
-            true
-        );
+        assert!(CoeffMultilinearPolynomial::<Fq>::get_variable_indexes(
+            4,
+            &[false, false, false, false]
+        )
+        .is_err());
+        assert!(CoeffMultilinearPolynomial::<Fq>::get_variable_indexes(
+            4,
+            &[true, false, true, false]
+        )
+        .is_err());
 
         // get all a indexes
         assert_eq!(
@@ -812,7 +951,7 @@ mod tests {
     fn test_evaluation_incomplete_assignment() {
         // p has 4 variables so requires 4 assignments
         let p = poly_5ab_7bc_8d();
-        assert_eq!(p.evaluate_slice(&[Fq::from(4)]).is_err(), true);
+        assert!(p.evaluate_slice(&[Fq::from(4)]).is_err());
     }
 
     #[test]
@@ -1244,6 +1383,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_index_checker_matches_product_of_checkers() {
+        for n_vars in 0..5 {
+            for index in 0..1 << n_vars {
+                // product of the single variable checkers, most significant bit first
+                let expected = (0..n_vars).rev().fold(
+                    CoeffMultilinearPolynomial::<Fq>::multiplicative_identity(),
+                    |acc, bit| {
+                        if (index >> bit) & 1 == 1 {
+                            &acc * &CoeffMultilinearPolynomial::check_one()
+                        } else {
+                            &acc * &CoeffMultilinearPolynomial::check_zero()
+                        }
+                    },
+                );
+                assert_eq!(
+                    CoeffMultilinearPolynomial::index_checker(index, n_vars),
+                    expected
+                );
+                // binary_string pads to at least one digit
+                if n_vars > 0 {
+                    assert_eq!(
+                        CoeffMultilinearPolynomial::bit_string_checker(binary_string(
+                            index, n_vars
+                        )),
+                        expected
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_bit_string_checker() {
         // poly to check 001
@@ -1341,8 +1512,91 @@ mod tests {
             p.to_evaluation_form(),
             vec![0, 0, 0, 3, 0, 0, 2, 5]
                 .into_iter()
-                .map(|v| Fq::from(v))
+                .map(Fq::from)
                 .collect::<Vec<_>>()
         );
     }
+
+    #[test]
+    fn test_from_evaluation_form() {
+        // y = [2, 4, 8, 3]
+        // p(a, b) = 2 + 6a + 2b - 7ab
+        let poly =
+            CoeffMultilinearPolynomial::<Fq>::from_evaluation_form(&fq_from_vec(vec![2, 4, 8, 3]))
+                .unwrap();
+        assert_eq!(poly.n_vars, 2);
+        assert_eq!(poly.coefficients, fq_map_from_vec(vec![2, 6, 2, -7]));
+
+        // should match interpolation
+        assert_eq!(
+            poly,
+            CoeffMultilinearPolynomial::<Fq>::interpolate(&fq_from_vec(vec![2, 4, 8, 3]))
+        );
+
+        // evaluation len must be a power of 2
+        assert!(CoeffMultilinearPolynomial::<Fq>::from_evaluation_form(&[]).is_err());
+        assert!(
+            CoeffMultilinearPolynomial::<Fq>::from_evaluation_form(&fq_from_vec(vec![1, 2, 3]))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_evaluation_form_round_trip() {
+        // coefficient form -> evaluation form -> coefficient form
+        let p = poly_5ab_7bc_8d();
+        let evaluations = p.to_evaluation_form();
+        assert_eq!(
+            CoeffMultilinearPolynomial::from_evaluation_form(&evaluations).unwrap(),
+            p
+        );
+
+        // evaluation form -> coefficient form -> evaluation form
+        let evaluations = fq_from_vec(vec![3, 0, 16, 5, 9, 1, 1, 12]);
+        let p = CoeffMultilinearPolynomial::from_evaluation_form(&evaluations).unwrap();
+        assert_eq!(p.to_evaluation_form(), evaluations);
+
+        // both representations should agree outside the boolean hypercube
+        let mle = MultiLinearPolynomial::new(3, evaluations).unwrap();
+        let point = fq_from_vec(vec![4, 11, 7]);
+        assert_eq!(
+            p.evaluate_slice(&point).unwrap(),
+            mle.evaluate(&point).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_interpolation_strategies_agree() {
+        // includes non power of two lengths (zero padded) and the single value case
+        for values in [
+            vec![5],
+            vec![2, 4, 8],
+            vec![3, 0, 16, 5, 9, 1, 1, 12],
+            vec![1, 1, 1, 1, 2, 2, 2, 2, 7, 0, 3],
+        ] {
+            let values = fq_from_vec(values);
+            let mobius = CoeffMultilinearPolynomial::<Fq>::interpolate_with(
+                &values,
+                InterpolationStrategy::Mobius,
+            );
+            let lagrange = CoeffMultilinearPolynomial::<Fq>::interpolate_with(
+                &values,
+                InterpolationStrategy::LagrangeBasis,
+            );
+            assert_eq!(mobius.n_vars, lagrange.n_vars);
+            assert_eq!(mobius.to_evaluation_form(), lagrange.to_evaluation_form());
+
+            let mut padded = values.clone();
+            padded.resize(1 << mobius.n_vars, Fq::from(0));
+            assert_eq!(mobius.to_evaluation_form(), padded);
+        }
+
+        assert_eq!(
+            CoeffMultilinearPolynomial::<Fq>::interpolate_with(
+                &[],
+                InterpolationStrategy::LagrangeBasis
+            ),
+            CoeffMultilinearPolynomial::<Fq>::interpolate(&[])
+        );
+    }
 }
@@ -1,5 +1,9 @@
+use crate::encoding::{self, Endianness};
+use crate::hypercube_sum::HypercubeSum;
 use crate::multilinear::pairing_index::index_pair;
-use ark_ff::{BigInteger, PrimeField};
+use ark_ff::PrimeField;
+use std::io::Write;
+use std::ops::Add;
 
 #[derive(Clone, Debug, PartialEq)]
 /// `MultilinearPolynomial` (Dense Evaluation Representation)
@@ -46,6 +50,10 @@ impl<F: PrimeField> MultiLinearPolynomial<F> {
         // evaluation route because consecutive partial eval is all that's needed for sumcheck and
         // gkr, and it seems random access partial evaluation will introduce additional cost (e.g. when
         // detecting duplicate assignments)
+        if initial_var + assignments.len() > self.n_vars {
+            return Err("partial evaluation assigns past the last variable");
+        }
+
         let mut new_evaluations = self.evaluations.clone();
 
         // for each assignment
@@ -73,10 +81,7 @@ impl<F: PrimeField> MultiLinearPolynomial<F> {
 
         // truncate and return new polynomial
         let new_n_vars = self.n_vars - assignments.len();
-        Ok(Self::new(
-            new_n_vars,
-            new_evaluations[..(1 << new_n_vars)].to_vec(),
-        )?)
+        Self::new(new_n_vars, new_evaluations[..(1 << new_n_vars)].to_vec())
     }
 
     /// Evaluate the `MultilinearPolynomial` at n points
@@ -88,6 +93,48 @@ impl<F: PrimeField> MultiLinearPolynomial<F> {
         Ok(self.partial_evaluate(0, assignments)?.evaluations[0])
     }
 
+    /// Fix the first variable to r, returning the half sized table
+    /// same result as partial_evaluate(0, &[r])
+    pub fn fold(&self, r: &F) -> Result<Self, &'static str> {
+        if self.n_vars == 0 {
+            return Err("cannot fold a polynomial with no variables");
+        }
+
+        Ok(Self {
+            n_vars: self.n_vars - 1,
+            evaluations: fold_first_variable(&self.evaluations, r),
+        })
+    }
+
+    /// Fix the first variable to r in place
+    pub fn fold_in_place(&mut self, r: &F) -> Result<(), &'static str> {
+        if self.n_vars == 0 {
+            return Err("cannot fold a polynomial with no variables");
+        }
+
+        let half = self.evaluations.len() / 2;
+        for i in 0..half {
+            let (left, right) = (self.evaluations[i], self.evaluations[i + half]);
+            self.evaluations[i] = left + *r * (right - left);
+        }
+        self.evaluations.truncate(half);
+        self.n_vars -= 1;
+        Ok(())
+    }
+
+    /// Fix the leading variables one challenge at a time
+    /// yields the table after each fold (n_vars - 1, n_vars - 2, ...)
+    pub fn fold_all<'a>(&self, challenges: &'a [F]) -> Result<Folds<'a, F>, &'static str> {
+        if challenges.len() > self.n_vars {
+            return Err("more challenges than variables");
+        }
+
+        Ok(Folds {
+            current: self.clone(),
+            challenges: challenges.iter(),
+        })
+    }
+
     /// Returns the evaluations of the `MultilinearPolynomial` as a slice
     pub fn evaluation_slice(&self) -> &[F] {
         &self.evaluations
@@ -95,33 +142,230 @@ impl<F: PrimeField> MultiLinearPolynomial<F> {
 
     /// Serialize the `MultilinearPolynomial`
     pub fn to_bytes(&self) -> Vec<u8> {
-        self.evaluations
+        self.to_bytes_with_endianness(Endianness::Big)
+    }
+
+    /// Serialize the `MultilinearPolynomial` with the given byte order
+    pub fn to_bytes_with_endianness(&self, endianness: Endianness) -> Vec<u8> {
+        encoding::to_bytes_with(|bytes| self.write_bytes_with_endianness(bytes, endianness))
+    }
+
+    /// Streams the `to_bytes` encoding to a writer
+    pub fn write_bytes<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.write_bytes_with_endianness(writer, Endianness::Big)
+    }
+
+    /// Streams the `to_bytes_with_endianness` encoding to a writer
+    pub fn write_bytes_with_endianness<W: Write>(
+        &self,
+        writer: &mut W,
+        endianness: Endianness,
+    ) -> std::io::Result<()> {
+        encoding::write_polynomial_header(writer, b"mle", self.n_vars, endianness)?;
+        encoding::write_field_elements(writer, &self.evaluations, endianness)
+    }
+
+    /// Evaluation wise multiplication with scalar
+    pub fn scalar_multiply(&self, scalar: &F) -> Self {
+        Self {
+            n_vars: self.n_vars,
+            evaluations: self.evaluations.iter().map(|eval| *eval * scalar).collect(),
+        }
+    }
+
+    /// Evaluation wise multiplication of two polynomials over the same variables
+    /// NOTE: the result is the multilinear extension of the product's hypercube evaluations
+    /// not the (higher degree) product polynomial itself
+    pub fn hadamard(&self, rhs: &Self) -> Result<Self, &'static str> {
+        if self.n_vars != rhs.n_vars {
+            return Err("hadamard product requires polynomials with the same number of variables");
+        }
+
+        Ok(Self {
+            n_vars: self.n_vars,
+            evaluations: self
+                .evaluations
+                .iter()
+                .zip(rhs.evaluations.iter())
+                .map(|(a, b)| *a * b)
+                .collect(),
+        })
+    }
+
+    /// Evaluate the `MultilinearPolynomial` at many points
+    /// points are visited in sorted order and the partially evaluated tables for the
+    /// current point's prefixes are kept, points sharing leading coordinates reuse them
+    /// (e.g. a batch with a common first coordinate folds the full table only once)
+    pub fn evaluate_batch(&self, points: &[Vec<F>]) -> Result<Vec<F>, &'static str> {
+        if points.iter().any(|point| point.len() != self.n_vars) {
+            return Err("evaluate must assign to all variables");
+        }
+
+        let mut order = (0..points.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| points[*a].cmp(&points[*b]));
+
+        let mut results = vec![F::ZERO; points.len()];
+
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            let chunk_size = order.len().div_ceil(rayon::current_num_threads()).max(1);
+            let evaluations = order
+                .par_chunks(chunk_size)
+                .map(|chunk| self.evaluate_sorted(chunk, points))
+                .collect::<Vec<_>>();
+            for (index, evaluation) in order.iter().zip(evaluations.into_iter().flatten()) {
+                results[*index] = evaluation;
+            }
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        for (index, evaluation) in order.iter().zip(self.evaluate_sorted(&order, points)) {
+            results[*index] = evaluation;
+        }
+
+        Ok(results)
+    }
+
+    /// Evaluates `points[i]` for each i in `order` (in that order)
+    /// tables[k] holds the evaluations with the first k coordinates of the previous point fixed
+    fn evaluate_sorted(&self, order: &[usize], points: &[Vec<F>]) -> Vec<F> {
+        let mut tables = vec![self.evaluations.clone()];
+        let mut previous: Option<&[F]> = None;
+
+        order
             .iter()
-            .map(|elem| elem.into_bigint().to_bytes_be())
-            .collect::<Vec<Vec<u8>>>()
-            .concat()
+            .map(|index| {
+                let point = points[*index].as_slice();
+                let shared_prefix = previous.map_or(0, |previous| {
+                    previous
+                        .iter()
+                        .zip(point.iter())
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                });
+                tables.truncate(shared_prefix + 1);
+
+                for coordinate in &point[shared_prefix..] {
+                    let table = tables.last().expect("tables always holds the full table");
+                    tables.push(fold_first_variable(table, coordinate));
+                }
+
+                previous = Some(point);
+                tables[self.n_vars][0]
+            })
+            .collect()
+    }
+}
+
+/// Fixes the first variable (most significant index bit) of an evaluation table
+/// (1 - r) * left + r * right, left and right being the two halves of the table
+fn fold_first_variable<F: PrimeField>(table: &[F], r: &F) -> Vec<F> {
+    let (left, right) = table.split_at(table.len() / 2);
+    left.iter()
+        .zip(right.iter())
+        .map(|(left, right)| *left + *r * (*right - left))
+        .collect()
+}
+
+impl<F: PrimeField> HypercubeSum<F> for MultiLinearPolynomial<F> {
+    fn hypercube_size(&self) -> usize {
+        self.evaluations.len()
+    }
+
+    fn hypercube_evaluation(&self, index: usize) -> F {
+        self.evaluations[index]
+    }
+}
+
+/// Iterator over the successive folds of a `MultilinearPolynomial`
+/// see `MultilinearPolynomial::fold_all`
+pub struct Folds<'a, F: PrimeField> {
+    current: MultiLinearPolynomial<F>,
+    challenges: std::slice::Iter<'a, F>,
+}
+
+impl<F: PrimeField> Iterator for Folds<'_, F> {
+    type Item = MultiLinearPolynomial<F>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let challenge = self.challenges.next()?;
+        // fold_all ensures there is a variable left for every challenge
+        self.current.fold_in_place(challenge).ok()?;
+        Some(self.current.clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.challenges.size_hint()
+    }
+}
+
+impl<F: PrimeField> Add for &MultiLinearPolynomial<F> {
+    type Output = Result<MultiLinearPolynomial<F>, &'static str>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        // unlike the coefficient form, the evaluation vectors have to be over the same
+        // boolean hypercube to be summed element wise
+        if self.n_vars != rhs.n_vars {
+            return Err("cannot add polynomials with different number of variables");
+        }
+
+        Ok(MultiLinearPolynomial {
+            n_vars: self.n_vars,
+            evaluations: self
+                .evaluations
+                .iter()
+                .zip(rhs.evaluations.iter())
+                .map(|(a, b)| *a + b)
+                .collect(),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<F: PrimeField> serde::Serialize for MultiLinearPolynomial<F> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::ark_serde::serialize(&self.evaluations, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, F: PrimeField> serde::Deserialize<'de> for MultiLinearPolynomial<F> {
+    /// n_vars is recovered from the evaluation count, which must be a power of 2
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let evaluations: Vec<F> = crate::ark_serde::deserialize(deserializer)?;
+        if !evaluations.len().is_power_of_two() {
+            return Err(serde::de::Error::custom(
+                "evaluation vec len should be a power of 2",
+            ));
+        }
+        let n_vars = evaluations.len().trailing_zeros() as usize;
+        Self::new(n_vars, evaluations).map_err(serde::de::Error::custom)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::multilinear::evaluation_form::MultiLinearPolynomial;
+    use crate::test_fields::random_mle;
     use ark_bls12_381::Fr;
+    use ark_ff::UniformRand;
 
     #[test]
     fn test_new_multilinear_poly() {
         // should not allow n_vars / evaluation count mismatch
         let poly = MultiLinearPolynomial::new(2, vec![Fr::from(3), Fr::from(1), Fr::from(2)]);
-        assert_eq!(poly.is_err(), true);
+        assert!(poly.is_err());
         let poly = MultiLinearPolynomial::new(2, vec![Fr::from(3), Fr::from(1)]);
-        assert_eq!(poly.is_err(), true);
+        assert!(poly.is_err());
 
         // correct inputs
         let poly = MultiLinearPolynomial::new(1, vec![Fr::from(3), Fr::from(1)]);
-        assert_eq!(poly.is_err(), false);
+        assert!(poly.is_ok());
         let poly =
             MultiLinearPolynomial::new(2, vec![Fr::from(3), Fr::from(1), Fr::from(2), Fr::from(5)]);
-        assert_eq!(poly.is_err(), false);
+        assert!(poly.is_ok());
     }
 
     #[test]
@@ -145,6 +389,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_partial_evaluate_out_of_range() {
+        let poly =
+            MultiLinearPolynomial::new(2, vec![Fr::from(3), Fr::from(1), Fr::from(2), Fr::from(5)])
+                .unwrap();
+        assert!(poly
+            .partial_evaluate(0, &[Fr::from(1), Fr::from(2), Fr::from(3)])
+            .is_err());
+        assert!(poly.partial_evaluate(2, &[Fr::from(1)]).is_err());
+        assert!(poly.partial_evaluate(2, &[]).is_ok());
+    }
+
     #[test]
     fn test_partial_evaluate_consecutive_variables() {
         // f(a, b, c) = 2ab + 3bc
@@ -200,4 +456,155 @@ mod tests {
             .unwrap();
         assert_eq!(evaluation_result, Fr::from(48));
     }
+
+    #[test]
+    fn test_polynomial_addition() {
+        // p = 2ab + 3bc
+        // q = a + 4c
+        let p = MultiLinearPolynomial::new(
+            3,
+            vec![0, 0, 0, 3, 0, 0, 2, 5]
+                .into_iter()
+                .map(Fr::from)
+                .collect(),
+        )
+        .unwrap();
+        let q = MultiLinearPolynomial::new(
+            3,
+            vec![0, 4, 0, 4, 1, 5, 1, 5]
+                .into_iter()
+                .map(Fr::from)
+                .collect(),
+        )
+        .unwrap();
+
+        let sum = (&p + &q).unwrap();
+        assert_eq!(
+            sum.evaluations,
+            vec![0, 4, 0, 7, 1, 5, 3, 10]
+                .into_iter()
+                .map(Fr::from)
+                .collect::<Vec<_>>()
+        );
+
+        // sum should also hold outside the boolean hypercube
+        let point = [Fr::from(2), Fr::from(3), Fr::from(4)];
+        assert_eq!(
+            sum.evaluate(&point).unwrap(),
+            p.evaluate(&point).unwrap() + q.evaluate(&point).unwrap()
+        );
+
+        // cannot add polynomials with different number of variables
+        let r = MultiLinearPolynomial::new(1, vec![Fr::from(1), Fr::from(2)]).unwrap();
+        assert!((&p + &r).is_err());
+    }
+
+    #[test]
+    fn test_scalar_multiplication() {
+        // p = 2ab + 3bc
+        // 2p = 4ab + 6bc
+        let p = MultiLinearPolynomial::new(
+            3,
+            vec![0, 0, 0, 3, 0, 0, 2, 5]
+                .into_iter()
+                .map(Fr::from)
+                .collect(),
+        )
+        .unwrap();
+        let two_p = p.scalar_multiply(&Fr::from(2));
+        assert_eq!(
+            two_p.evaluations,
+            vec![0, 0, 0, 6, 0, 0, 4, 10]
+                .into_iter()
+                .map(Fr::from)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            two_p
+                .evaluate(&[Fr::from(2), Fr::from(3), Fr::from(4)])
+                .unwrap(),
+            Fr::from(96)
+        );
+    }
+
+    #[test]
+    fn test_hadamard_product() {
+        let p =
+            MultiLinearPolynomial::new(2, vec![Fr::from(3), Fr::from(1), Fr::from(2), Fr::from(5)])
+                .unwrap();
+        let q =
+            MultiLinearPolynomial::new(2, vec![Fr::from(2), Fr::from(0), Fr::from(7), Fr::from(3)])
+                .unwrap();
+        let pq = p.hadamard(&q).unwrap();
+        assert_eq!(
+            pq.evaluations,
+            vec![Fr::from(6), Fr::from(0), Fr::from(14), Fr::from(15)]
+        );
+
+        // cannot multiply polynomials with different number of variables
+        let r = MultiLinearPolynomial::new(1, vec![Fr::from(1), Fr::from(2)]).unwrap();
+        assert!(p.hadamard(&r).is_err());
+    }
+
+    #[test]
+    fn test_evaluate_batch() {
+        let mut rng = ark_std::test_rng();
+        let n_vars = 4;
+        let poly = random_mle::<Fr, _>(n_vars, &mut rng);
+
+        let shared = Fr::rand(&mut rng);
+        let mut points = (0..6)
+            .map(|_| (0..n_vars).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        // points sharing leading coordinates, duplicates and hypercube corners
+        points.extend((0..4).map(|i| vec![shared, Fr::from(i % 2), Fr::rand(&mut rng), shared]));
+        points.push(points[0].clone());
+        points.extend((0..1 << n_vars).map(|index| {
+            (0..n_vars)
+                .rev()
+                .map(|bit| Fr::from(((index >> bit) & 1) as u64))
+                .collect()
+        }));
+
+        let expected = points
+            .iter()
+            .map(|point| poly.evaluate(point).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(poly.evaluate_batch(&points).unwrap(), expected);
+        assert_eq!(poly.evaluate_batch(&[]).unwrap(), vec![]);
+
+        // every point must assign all variables
+        assert!(poly
+            .evaluate_batch(&[points[0].clone(), vec![Fr::from(1)]])
+            .is_err());
+    }
+
+    #[test]
+    fn test_fold() {
+        let mut rng = ark_std::test_rng();
+        let poly = random_mle::<Fr, _>(3, &mut rng);
+        let challenges = (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+
+        let folded = poly.fold(&challenges[0]).unwrap();
+        assert_eq!(folded, poly.partial_evaluate(0, &challenges[..1]).unwrap());
+
+        let mut in_place = poly.clone();
+        in_place.fold_in_place(&challenges[0]).unwrap();
+        assert_eq!(in_place, folded);
+
+        let folds = poly.fold_all(&challenges).unwrap().collect::<Vec<_>>();
+        assert_eq!(folds.len(), 3);
+        for (i, fold) in folds.iter().enumerate() {
+            assert_eq!(fold, &poly.partial_evaluate(0, &challenges[..=i]).unwrap());
+        }
+        assert_eq!(
+            folds[2].evaluation_slice()[0],
+            poly.evaluate(&challenges).unwrap()
+        );
+
+        assert!(folds[2].fold(&challenges[0]).is_err());
+        assert!(poly
+            .fold_all(&[challenges.clone(), challenges].concat())
+            .is_err());
+    }
 }
@@ -0,0 +1,117 @@
+//! Evaluation of multilinear polynomials at integer points
+//! shorthand for tests and examples, `poly.evaluate_at_bits(0b101)` instead of spelling out
+//! `[F::from(1), F::from(0), F::from(1)]`, the `point!` macro builds such slices in place
+
+use crate::multilinear::coefficient_form::CoeffMultilinearPolynomial;
+use crate::multilinear::evaluation_form::MultiLinearPolynomial;
+use crate::Polynomial;
+use ark_ff::PrimeField;
+
+/// Builds an evaluation point from integers, the field is inferred from the use site
+/// e.g. `poly.evaluate(point![1, 0, -5])`, negative values wrap around the modulus
+#[macro_export]
+macro_rules! point {
+    ($($value:expr),* $(,)?) => {
+        &[$(::core::convert::From::from($value as i64)),*][..]
+    };
+}
+
+/// Integer point evaluation for multilinear polynomials
+/// variable 0 is the most significant bit of a hypercube index
+pub trait IntegerEvaluation<F: PrimeField> {
+    /// Returns the number of variables
+    fn variable_count(&self) -> usize;
+
+    /// Evaluates at a point with every variable assigned
+    fn evaluate_point(&self, point: &[F]) -> Result<F, &'static str>;
+
+    /// Evaluates at the boolean hypercube corner with the given index
+    fn evaluate_at_bits(&self, index: usize) -> Result<F, &'static str> {
+        let n_vars = self.variable_count();
+        if n_vars < usize::BITS as usize && index >> n_vars != 0 {
+            return Err("hypercube index out of range");
+        }
+
+        let point = (0..n_vars)
+            .map(|i| {
+                if (index >> (n_vars - 1 - i)) & 1 == 1 {
+                    F::ONE
+                } else {
+                    F::ZERO
+                }
+            })
+            .collect::<Vec<_>>();
+        self.evaluate_point(&point)
+    }
+
+    /// Evaluates at a point given as integers
+    fn evaluate_usize(&self, point: &[u64]) -> Result<F, &'static str> {
+        let point = point
+            .iter()
+            .map(|value| F::from(*value))
+            .collect::<Vec<_>>();
+        self.evaluate_point(&point)
+    }
+}
+
+impl<F: PrimeField> IntegerEvaluation<F> for MultiLinearPolynomial<F> {
+    fn variable_count(&self) -> usize {
+        self.n_vars()
+    }
+
+    fn evaluate_point(&self, point: &[F]) -> Result<F, &'static str> {
+        self.evaluate(point)
+    }
+
+    /// Reads the evaluation straight from the table
+    fn evaluate_at_bits(&self, index: usize) -> Result<F, &'static str> {
+        self.evaluation_slice()
+            .get(index)
+            .copied()
+            .ok_or("hypercube index out of range")
+    }
+}
+
+impl<F: PrimeField> IntegerEvaluation<F> for CoeffMultilinearPolynomial<F> {
+    fn variable_count(&self) -> usize {
+        self.n_vars()
+    }
+
+    fn evaluate_point(&self, point: &[F]) -> Result<F, &'static str> {
+        self.evaluate_slice(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntegerEvaluation;
+    use crate::fq_vec;
+    use crate::multilinear::coefficient_form::CoeffMultilinearPolynomial;
+    use crate::multilinear::evaluation_form::MultiLinearPolynomial;
+    use crate::test_fields::F17 as Fq;
+
+    #[test]
+    fn test_integer_evaluation() {
+        // f(a, b) = 3 + 2a + 5b + ab
+        let evaluations = fq_vec![Fq; 3, 8, 5, 11];
+        let mle = MultiLinearPolynomial::new(2, evaluations.clone()).unwrap();
+        let coeff = CoeffMultilinearPolynomial::from_evaluation_form(&evaluations).unwrap();
+
+        for (index, expected) in evaluations.iter().enumerate() {
+            assert_eq!(mle.evaluate_at_bits(index).unwrap(), *expected);
+            assert_eq!(coeff.evaluate_at_bits(index).unwrap(), *expected);
+        }
+        assert!(mle.evaluate_at_bits(4).is_err());
+        assert!(coeff.evaluate_at_bits(4).is_err());
+
+        // f(2, 3) = 3 + 4 + 15 + 6 = 28 = 11 mod 17
+        assert_eq!(mle.evaluate_usize(&[2, 3]).unwrap(), Fq::from(11));
+        assert_eq!(coeff.evaluate_usize(&[2, 3]).unwrap(), Fq::from(11));
+        assert_eq!(mle.evaluate(point![2, 3]).unwrap(), Fq::from(11));
+        // f(-1, 2) = 3 - 2 + 10 - 2 = 9
+        assert_eq!(mle.evaluate(point![-1, 2]).unwrap(), Fq::from(9));
+        let negative: &[Fq] = point![-1, -17];
+        assert_eq!(negative, &[Fq::from(16), Fq::from(0)]);
+        assert!(mle.evaluate_usize(&[2]).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 mod boolean_hypercube;
 pub mod coefficient_form;
 pub mod evaluation_form;
+pub mod integer_evaluation;
 pub mod pairing_index;
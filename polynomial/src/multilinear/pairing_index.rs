@@ -4,7 +4,7 @@ pub fn index_pair(n_vars: u8, index: u8) -> impl Iterator<Item = (usize, usize)>
     let no_of_pairs = 1 << base_no_of_vars;
     (0..no_of_pairs).map(move |val| {
         let insert_0 = insert_bit(val, base_no_of_vars - index, 0);
-        (insert_0, insert_0 | (1 << base_no_of_vars - index))
+        (insert_0, insert_0 | (1 << (base_no_of_vars - index)))
     })
 }
 
@@ -0,0 +1,233 @@
+use crate::hypercube_sum::{HypercubeSum, SumMode};
+use crate::SumcheckPolynomial;
+use ark_ff::PrimeField;
+use std::fmt;
+use std::io::Write;
+use std::sync::Arc;
+
+/// Evaluation oracle, returns P(point) for a point assigning every variable
+pub type Oracle<F> = Arc<dyn Fn(&[F]) -> F + Send + Sync>;
+
+/// Polynomial defined by an evaluation oracle instead of an evaluation table
+/// e.g. eq(r, x).f(x).g(x) computed on demand, nothing of size 2^n is stored.
+/// The degree bound is trusted, it decides how many points each round polynomial is
+/// evaluated at, an oracle with a higher variable degree yields an invalid proof.
+/// Each round calls the oracle (degree + 1) * 2^(n - 1) times, n the free variables left.
+#[derive(Clone)]
+pub struct OraclePoly<F: PrimeField> {
+    n_vars: usize,
+    degree: usize,
+    oracle: Oracle<F>,
+    fixed: Vec<F>,
+}
+
+impl<F: PrimeField> OraclePoly<F> {
+    /// Instantiate a new oracle poly over n_vars variables, each of degree at most `degree`
+    pub fn new(
+        n_vars: usize,
+        degree: usize,
+        oracle: impl Fn(&[F]) -> F + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            n_vars,
+            degree,
+            oracle: Arc::new(oracle),
+            fixed: vec![],
+        }
+    }
+
+    /// Return the number of free variables (variables not yet fixed)
+    pub fn n_vars(&self) -> usize {
+        self.n_vars - self.fixed.len()
+    }
+
+    /// Returns the degree bound of every variable
+    pub fn max_variable_degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Returns the values the leading variables have been fixed to
+    pub fn fixed(&self) -> &[F] {
+        &self.fixed
+    }
+
+    /// Evaluate at the fixed variables followed by the given assignments
+    pub fn evaluate(&self, assignments: &[F]) -> Result<F, &'static str> {
+        if assignments.len() != self.n_vars() {
+            return Err("evaluate must assign to all variables");
+        }
+
+        let point = [self.fixed.as_slice(), assignments].concat();
+        Ok((self.oracle)(&point))
+    }
+
+    /// Evaluations of the round polynomial at 0, 1, ..., degree
+    /// g(X) = sum_{x in {0, 1}^(n - 1)} P(fixed, X, x)
+    pub fn round_evaluations(&self) -> Result<Vec<F>, &'static str> {
+        let free = self.n_vars();
+        if free == 0 {
+            return Err("round polynomial requires at least one variable");
+        }
+
+        let round_var = self.fixed.len();
+        let mut point = self.point_buffer();
+        Ok((0..=self.degree)
+            .map(|t| {
+                point[round_var] = F::from(t as u64);
+                fold_hypercube(&mut point, round_var + 1, F::zero(), |sum, point| {
+                    sum + (self.oracle)(point)
+                })
+            })
+            .collect())
+    }
+
+    /// The fixed variables followed by zeroed free variables
+    fn point_buffer(&self) -> Vec<F> {
+        [self.fixed.as_slice(), &vec![F::zero(); self.n_vars()]].concat()
+    }
+
+    /// Fixes the first free variable to r
+    pub fn fold_in_place(&mut self, r: &F) -> Result<(), &'static str> {
+        if self.n_vars() == 0 {
+            return Err("cannot fold a polynomial with no variables");
+        }
+
+        self.fixed.push(*r);
+        Ok(())
+    }
+}
+
+/// Walks the boolean hypercube over point[start..] in index order, folding every point
+/// into the accumulator, the same buffer is rewritten for each point
+fn fold_hypercube<F: PrimeField, B>(
+    point: &mut [F],
+    start: usize,
+    init: B,
+    mut f: impl FnMut(B, &[F]) -> B,
+) -> B {
+    let mut acc = init;
+    for index in 0..1_usize << (point.len() - start) {
+        set_hypercube_index(&mut point[start..], index);
+        acc = f(acc, point);
+    }
+    acc
+}
+
+/// Writes the bits of index to the variables, the first one most significant
+fn set_hypercube_index<F: PrimeField>(variables: &mut [F], index: usize) {
+    let len = variables.len();
+    for (i, value) in variables.iter_mut().enumerate() {
+        *value = F::from(((index >> (len - 1 - i)) & 1) as u64);
+    }
+}
+
+/// Sums over the free variables, the fixed ones keep their values
+impl<F: PrimeField> HypercubeSum<F> for OraclePoly<F> {
+    fn hypercube_size(&self) -> usize {
+        1 << self.n_vars()
+    }
+
+    fn hypercube_evaluation(&self, index: usize) -> F {
+        let mut point = self.point_buffer();
+        set_hypercube_index(&mut point[self.fixed.len()..], index);
+        (self.oracle)(&point)
+    }
+
+    /// Reuses one point buffer (per thread when parallel) instead of one per index
+    fn sum_over_boolean_hypercube_with(&self, mode: SumMode) -> F {
+        match mode {
+            #[cfg(feature = "parallel")]
+            SumMode::Parallel => {
+                use rayon::prelude::*;
+
+                (0..self.hypercube_size())
+                    .into_par_iter()
+                    .map_init(
+                        || self.point_buffer(),
+                        |point, index| {
+                            set_hypercube_index(&mut point[self.fixed.len()..], index);
+                            (self.oracle)(point)
+                        },
+                    )
+                    .sum()
+            }
+            _ => fold_hypercube(
+                &mut self.point_buffer(),
+                self.fixed.len(),
+                F::zero(),
+                |sum, point| sum + (self.oracle)(point),
+            ),
+        }
+    }
+}
+
+impl<F: PrimeField> SumcheckPolynomial<F> for OraclePoly<F> {
+    fn n_vars(&self) -> usize {
+        self.n_vars()
+    }
+
+    fn max_variable_degree(&self) -> usize {
+        self.max_variable_degree()
+    }
+
+    fn round_evaluations(&self) -> Result<Vec<F>, &'static str> {
+        self.round_evaluations()
+    }
+
+    fn fold_in_place(&mut self, r: &F) -> Result<(), &'static str> {
+        self.fold_in_place(r)
+    }
+
+    /// An oracle has no encoding, proofs over it are partial or bound to a commitment
+    fn write_transcript_bytes<W: Write>(&self, _writer: &mut W) -> std::io::Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "oracle polys have no encoding",
+        ))
+    }
+}
+
+impl<F: PrimeField> fmt::Debug for OraclePoly<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OraclePoly")
+            .field("n_vars", &self.n_vars)
+            .field("degree", &self.degree)
+            .field("fixed", &self.fixed)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OraclePoly;
+    use crate::test_fields::random_product_poly;
+    use ark_bls12_381::Fr;
+    use ark_ff::UniformRand;
+
+    #[test]
+    fn test_oracle_matches_product_poly() {
+        let mut rng = ark_std::test_rng();
+        let mut product = random_product_poly::<Fr, _>(4, 3, &mut rng);
+
+        let table = product.clone();
+        let mut oracle = OraclePoly::new(4, 3, move |point: &[Fr]| table.evaluate(point).unwrap());
+
+        for _ in 0..4 {
+            assert_eq!(
+                oracle.round_evaluations().unwrap(),
+                product.round_evaluations().unwrap()
+            );
+            let r = Fr::rand(&mut rng);
+            oracle.fold_in_place(&r).unwrap();
+            product.fold_in_place(&r).unwrap();
+        }
+
+        assert_eq!(oracle.n_vars(), 0);
+        assert_eq!(
+            oracle.evaluate(&[]).unwrap(),
+            product.evaluate(&[]).unwrap()
+        );
+        assert!(oracle.round_evaluations().is_err());
+        assert!(oracle.fold_in_place(&Fr::from(1)).is_err());
+    }
+}
@@ -1,5 +1,9 @@
+use crate::encoding::{self, Endianness};
+use crate::hypercube_sum::HypercubeSum;
 use crate::multilinear::evaluation_form::MultiLinearPolynomial;
+use crate::SumcheckPolynomial;
 use ark_ff::PrimeField;
+use std::io::Write;
 
 /// Represents the product of one or more `Multilinear` polynomials
 /// P(x) = A(x).B(x).C(x)
@@ -12,7 +16,7 @@ pub struct ProductPoly<F: PrimeField> {
 impl<F: PrimeField> ProductPoly<F> {
     /// Instantiate a new product_poly from a set of `Multilinear` polynomials
     pub fn new(polynomials: Vec<MultiLinearPolynomial<F>>) -> Result<Self, &'static str> {
-        if polynomials.len() == 0 {
+        if polynomials.is_empty() {
             return Err("cannot create product polynomial from empty polynomials");
         }
 
@@ -75,24 +79,309 @@ impl<F: PrimeField> ProductPoly<F> {
 
     /// Serialize the ProductPoly
     pub fn to_bytes(&self) -> Vec<u8> {
-        self.polynomials
-            .iter()
-            .map(|poly| poly.to_bytes())
-            .collect::<Vec<Vec<u8>>>()
-            .concat()
+        self.to_bytes_with_endianness(Endianness::Big)
+    }
+
+    /// Serialize the ProductPoly with the given byte order
+    pub fn to_bytes_with_endianness(&self, endianness: Endianness) -> Vec<u8> {
+        encoding::to_bytes_with(|bytes| self.write_bytes_with_endianness(bytes, endianness))
+    }
+
+    /// Streams the `to_bytes` encoding to a writer
+    pub fn write_bytes<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.write_bytes_with_endianness(writer, Endianness::Big)
+    }
+
+    /// Streams the `to_bytes_with_endianness` encoding to a writer
+    pub fn write_bytes_with_endianness<W: Write>(
+        &self,
+        writer: &mut W,
+        endianness: Endianness,
+    ) -> std::io::Result<()> {
+        encoding::write_polynomial_header(writer, b"product", self.n_vars, endianness)?;
+        encoding::write_length(writer, self.polynomials.len(), endianness)?;
+        for poly in &self.polynomials {
+            poly.write_bytes_with_endianness(writer, endianness)?;
+        }
+        Ok(())
     }
 
     /// Return the number of variables
     pub fn n_vars(&self) -> usize {
         self.n_vars
     }
+
+    /// Returns the max degree of any single variable in the product poly
+    /// each factor is multilinear, so this is the number of factors
+    pub fn max_variable_degree(&self) -> usize {
+        self.polynomials.len()
+    }
+
+    /// Fixes the leading variables to the given prefix and sums the product over
+    /// the boolean hypercube of the remaining variables
+    /// sum_{x in {0, 1}^(n - k)} P(prefix, x), k = prefix.len()
+    pub fn sum_with_fixed_prefix(&self, prefix: &[F]) -> Result<F, &'static str> {
+        Ok(self
+            .lazy()
+            .fix_variables(prefix)?
+            .prod_reduce()
+            .sum_over_boolean_hypercube())
+    }
+
+    /// Lazily computes `sum_with_fixed_prefix(&[point])` for each point
+    /// i.e. evaluations of the univariate round polynomial
+    /// g(X) = sum_{x in {0, 1}^(n - 1)} P(X, x)
+    pub fn round_evaluations_iter<'a>(
+        &'a self,
+        points: impl IntoIterator<Item = F> + 'a,
+    ) -> impl Iterator<Item = Result<F, &'static str>> + 'a {
+        points
+            .into_iter()
+            .map(move |point| self.sum_with_fixed_prefix(&[point]))
+    }
+
+    /// Evaluates the round polynomial (first variable free, the rest summed over the hypercube)
+    /// at `partial_evaluation_points(max_variable_degree)`, enough points to interpolate it
+    ///
+    /// Uses the folded table trick: each factor restricted to a hypercube point x is the line
+    /// left + X * (right - left), so its values at 0, 1, ..., d come from repeatedly adding
+    /// the slope. One pass over the tables gives every evaluation, no table is rebuilt.
+    pub fn round_evaluations(&self) -> Result<Vec<F>, &'static str> {
+        self.round_evaluations_with_degree(self.max_variable_degree())
+    }
+
+    /// Same as `round_evaluations` but at `partial_evaluation_points(degree)`
+    /// degree can be above the max variable degree, used when this poly is part of a sum
+    pub(crate) fn round_evaluations_with_degree(
+        &self,
+        degree: usize,
+    ) -> Result<Vec<F>, &'static str> {
+        if self.n_vars == 0 {
+            return Err("round polynomial requires at least one variable");
+        }
+
+        let half = 1 << (self.n_vars - 1);
+        let mut sums = vec![F::zero(); degree + 1];
+        let mut products = vec![F::one(); degree + 1];
+
+        for i in 0..half {
+            products.iter_mut().for_each(|product| *product = F::one());
+            for poly in &self.polynomials {
+                let evaluations = poly.evaluation_slice();
+                let (mut value, slope) = (evaluations[i], evaluations[i + half] - evaluations[i]);
+                for product in products.iter_mut() {
+                    *product *= value;
+                    value += slope;
+                }
+            }
+            for (sum, product) in sums.iter_mut().zip(products.iter()) {
+                *sum += product;
+            }
+        }
+
+        Ok(sums)
+    }
+
+    /// Fixes the first variable of every factor to r in place
+    pub fn fold_in_place(&mut self, r: &F) -> Result<(), &'static str> {
+        for poly in self.polynomials.iter_mut() {
+            poly.fold_in_place(r)?;
+        }
+        self.n_vars = self.polynomials[0].n_vars();
+        Ok(())
+    }
+
+    /// Returns a `LazyProductPoly` view over this product poly
+    /// partial evaluations on the view are recorded but not applied
+    pub fn lazy(&self) -> LazyProductPoly<'_, F> {
+        LazyProductPoly {
+            poly: self,
+            fixed: vec![],
+        }
+    }
+}
+
+impl<F: PrimeField> SumcheckPolynomial<F> for ProductPoly<F> {
+    fn n_vars(&self) -> usize {
+        self.n_vars()
+    }
+
+    fn max_variable_degree(&self) -> usize {
+        self.max_variable_degree()
+    }
+
+    fn round_evaluations(&self) -> Result<Vec<F>, &'static str> {
+        self.round_evaluations()
+    }
+
+    fn fold_in_place(&mut self, r: &F) -> Result<(), &'static str> {
+        self.fold_in_place(r)
+    }
+
+    fn write_transcript_bytes<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.write_bytes(writer)
+    }
+}
+
+impl<F: PrimeField> HypercubeSum<F> for ProductPoly<F> {
+    fn hypercube_size(&self) -> usize {
+        1 << self.n_vars
+    }
+
+    fn hypercube_evaluation(&self, index: usize) -> F {
+        self.polynomials
+            .iter()
+            .map(|poly| poly.evaluation_slice()[index])
+            .product()
+    }
+}
+
+/// Returns the points a round polynomial of the given degree is evaluated at: 0, 1, ..., degree
+/// d + 1 evaluations uniquely determine a univariate polynomial of degree d
+pub fn partial_evaluation_points<F: PrimeField>(degree: usize) -> Vec<F> {
+    (0..=degree).map(|i| F::from(i as u64)).collect()
+}
+
+/// Deferred partial evaluation view over a `ProductPoly`
+/// records assignments to the leading variables without rebuilding any evaluation table.
+/// Fixed variables are only applied when `evaluate`, `prod_reduce` or `materialize` is called,
+/// at that point all of them are folded into each factor in a single pass.
+#[derive(Clone, Debug)]
+pub struct LazyProductPoly<'a, F: PrimeField> {
+    poly: &'a ProductPoly<F>,
+    fixed: Vec<F>,
+}
+
+impl<'a, F: PrimeField> LazyProductPoly<'a, F> {
+    /// Return the number of free variables (variables not yet fixed)
+    pub fn n_vars(&self) -> usize {
+        self.poly.n_vars - self.fixed.len()
+    }
+
+    /// Fix the next leading free variables
+    /// e.g. for P(a, b, c) with a already fixed, fix_variables([2]) sets b = 2
+    pub fn fix_variables(mut self, assignments: &[F]) -> Result<Self, &'static str> {
+        if assignments.len() > self.n_vars() {
+            return Err("cannot fix more variables than the polynomial has");
+        }
+        self.fixed.extend_from_slice(assignments);
+        Ok(self)
+    }
+
+    /// Evaluate the product poly at the fixed variables followed by the given assignments
+    pub fn evaluate(&self, assignments: &[F]) -> Result<F, &'static str> {
+        if assignments.len() != self.n_vars() {
+            return Err("evaluate must assign to all variables");
+        }
+
+        let full_assignment = [self.fixed.as_slice(), assignments].concat();
+        self.poly.evaluate(&full_assignment)
+    }
+
+    /// Returns the element wise product of the factor evaluations after applying
+    /// all fixed variables, without building the intermediate factor tables
+    pub fn prod_reduce(&self) -> Vec<F> {
+        if self.fixed.is_empty() {
+            return self.poly.prod_reduce();
+        }
+
+        let weights = self.fixed_weights();
+        let stride = 1 << self.n_vars();
+        let mut result = vec![F::one(); stride];
+        for polynomial in &self.poly.polynomials {
+            let evaluations = polynomial.evaluation_slice();
+            for (i, value) in result.iter_mut().enumerate() {
+                *value *= fold_at(evaluations, &weights, stride, i);
+            }
+        }
+        result
+    }
+
+    /// Applies all fixed variables, returns the reduced `ProductPoly`
+    pub fn materialize(&self) -> Result<ProductPoly<F>, &'static str> {
+        if self.fixed.is_empty() {
+            return Ok(self.poly.clone());
+        }
+
+        let weights = self.fixed_weights();
+        let stride = 1 << self.n_vars();
+        let polynomials = self
+            .poly
+            .polynomials
+            .iter()
+            .map(|polynomial| {
+                let evaluations = polynomial.evaluation_slice();
+                MultiLinearPolynomial::new(
+                    self.n_vars(),
+                    (0..stride)
+                        .map(|i| fold_at(evaluations, &weights, stride, i))
+                        .collect(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        ProductPoly::new(polynomials)
+    }
+
+    /// Computes eq(fixed, b) for every b over the boolean hypercube of the fixed variables
+    /// only non-zero weights are returned, paired with the index of b
+    fn fixed_weights(&self) -> Vec<(usize, F)> {
+        // the first variable is the most significant bit of the evaluation index
+        // so each new variable doubles the table, (1 - r) for the 0 branch, r for the 1 branch
+        let mut weights = vec![F::one()];
+        for r in &self.fixed {
+            weights = weights
+                .iter()
+                .flat_map(|weight| [*weight * (F::one() - r), *weight * r])
+                .collect();
+        }
+
+        weights
+            .into_iter()
+            .enumerate()
+            .filter(|(_, weight)| !weight.is_zero())
+            .collect()
+    }
+}
+
+/// Folds the fixed variables into a single entry of the reduced table
+/// reduced[i] = sum over b of eq(fixed, b) * evaluations[b * stride + i]
+fn fold_at<F: PrimeField>(evaluations: &[F], weights: &[(usize, F)], stride: usize, i: usize) -> F {
+    weights
+        .iter()
+        .map(|(b, weight)| {
+            let evaluation = evaluations[b * stride + i];
+            if weight.is_one() {
+                evaluation
+            } else {
+                evaluation * weight
+            }
+        })
+        .sum()
+}
+
+#[cfg(feature = "serde")]
+impl<F: PrimeField> serde::Serialize for ProductPoly<F> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.polynomials.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, F: PrimeField> serde::Deserialize<'de> for ProductPoly<F> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let polynomials = Vec::<MultiLinearPolynomial<F>>::deserialize(deserializer)?;
+        Self::new(polynomials).map_err(serde::de::Error::custom)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::multilinear::evaluation_form::MultiLinearPolynomial;
-    use crate::product_poly::ProductPoly;
+    use crate::product_poly::{partial_evaluation_points, ProductPoly};
+    use crate::test_fields::random_product_poly;
     use ark_bls12_381::Fr;
+    use ark_ff::UniformRand;
 
     #[test]
     fn test_product_poly_creation() {
@@ -117,7 +406,7 @@ mod tests {
         )
         .unwrap();
         let prod_poly = ProductPoly::new(vec![mle_a, mle_b]);
-        assert_eq!(prod_poly.is_err(), true);
+        assert!(prod_poly.is_err());
     }
 
     #[test]
@@ -150,6 +439,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_max_variable_degree() {
+        let mle = MultiLinearPolynomial::new(1, vec![Fr::from(2), Fr::from(8)]).unwrap();
+        let prod_poly = ProductPoly::new(vec![mle.clone()]).unwrap();
+        assert_eq!(prod_poly.max_variable_degree(), 1);
+        let prod_poly = ProductPoly::new(vec![mle.clone(), mle.clone(), mle]).unwrap();
+        assert_eq!(prod_poly.max_variable_degree(), 3);
+    }
+
     #[test]
     fn test_partial_evaluate() {
         let mle_a = MultiLinearPolynomial::new(
@@ -194,4 +492,159 @@ mod tests {
             vec![Fr::from(4), Fr::from(64), Fr::from(100), Fr::from(308)]
         );
     }
+
+    #[test]
+    fn test_lazy_partial_evaluate() {
+        let mle_a = MultiLinearPolynomial::new(
+            3,
+            vec![
+                Fr::from(2),
+                Fr::from(8),
+                Fr::from(10),
+                Fr::from(14),
+                Fr::from(3),
+                Fr::from(0),
+                Fr::from(7),
+                Fr::from(5),
+            ],
+        )
+        .unwrap();
+        let mle_b = MultiLinearPolynomial::new(
+            3,
+            vec![
+                Fr::from(1),
+                Fr::from(4),
+                Fr::from(9),
+                Fr::from(22),
+                Fr::from(6),
+                Fr::from(11),
+                Fr::from(0),
+                Fr::from(2),
+            ],
+        )
+        .unwrap();
+        let prod_poly = ProductPoly::new(vec![mle_a, mle_b]).unwrap();
+
+        // fixing one variable at a time should match eager partial evaluation
+        let eager = prod_poly
+            .partial_evaluate(0, &[Fr::from(3)])
+            .unwrap()
+            .partial_evaluate(0, &[Fr::from(5)])
+            .unwrap();
+        let lazy = prod_poly
+            .lazy()
+            .fix_variables(&[Fr::from(3)])
+            .unwrap()
+            .fix_variables(&[Fr::from(5)])
+            .unwrap();
+
+        assert_eq!(lazy.n_vars(), 1);
+        assert_eq!(lazy.prod_reduce(), eager.prod_reduce());
+        assert_eq!(lazy.materialize().unwrap(), eager);
+        assert_eq!(
+            lazy.evaluate(&[Fr::from(7)]).unwrap(),
+            prod_poly
+                .evaluate(&[Fr::from(3), Fr::from(5), Fr::from(7)])
+                .unwrap()
+        );
+
+        // boolean assignments
+        let eager = prod_poly.partial_evaluate(0, &[Fr::from(1)]).unwrap();
+        let lazy = prod_poly.lazy().fix_variables(&[Fr::from(1)]).unwrap();
+        assert_eq!(lazy.prod_reduce(), eager.prod_reduce());
+
+        // no fixed variable
+        assert_eq!(prod_poly.lazy().prod_reduce(), prod_poly.prod_reduce());
+        assert_eq!(prod_poly.lazy().materialize().unwrap(), prod_poly);
+
+        // cannot fix more variables than the poly has
+        assert!(prod_poly
+            .lazy()
+            .fix_variables(&[Fr::from(1), Fr::from(2)])
+            .unwrap()
+            .fix_variables(&[Fr::from(3), Fr::from(4)])
+            .is_err());
+    }
+
+    #[test]
+    fn test_sum_with_fixed_prefix() {
+        let mut rng = ark_std::test_rng();
+        for n_vars in 1..5 {
+            let prod_poly = random_product_poly(n_vars, 3, &mut rng);
+            for prefix_len in 0..=n_vars {
+                let prefix = (0..prefix_len)
+                    .map(|_| Fr::rand(&mut rng))
+                    .collect::<Vec<_>>();
+
+                // naive: evaluate at every (prefix, x) with x on the remaining hypercube
+                let remaining = n_vars - prefix_len;
+                let expected = (0..1 << remaining)
+                    .map(|index| {
+                        let mut point = prefix.clone();
+                        point.extend(
+                            (0..remaining)
+                                .rev()
+                                .map(|bit| Fr::from(((index >> bit) & 1) as u64)),
+                        );
+                        prod_poly.evaluate(&point).unwrap()
+                    })
+                    .sum::<Fr>();
+
+                assert_eq!(prod_poly.sum_with_fixed_prefix(&prefix).unwrap(), expected);
+            }
+            assert!(prod_poly
+                .sum_with_fixed_prefix(&vec![Fr::from(1); n_vars + 1])
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_round_evaluations() {
+        let prod_poly = random_product_poly::<Fr, _>(3, 2, &mut ark_std::test_rng());
+        let points = partial_evaluation_points::<Fr>(prod_poly.max_variable_degree());
+        assert_eq!(points, vec![Fr::from(0), Fr::from(1), Fr::from(2)]);
+
+        let round_evaluations = prod_poly.round_evaluations().unwrap();
+        let from_iter = prod_poly
+            .round_evaluations_iter(points.clone())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(round_evaluations, from_iter);
+
+        for (point, evaluation) in points.iter().zip(round_evaluations.iter()) {
+            assert_eq!(
+                prod_poly.sum_with_fixed_prefix(&[*point]).unwrap(),
+                *evaluation
+            );
+        }
+
+        // g(0) + g(1) is the sum over the full hypercube
+        assert_eq!(
+            round_evaluations[0] + round_evaluations[1],
+            prod_poly.sum_with_fixed_prefix(&[]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_fold_in_place() {
+        let prod_poly = random_product_poly::<Fr, _>(3, 3, &mut ark_std::test_rng());
+        let challenge = Fr::from(7);
+
+        let mut folded = prod_poly.clone();
+        folded.fold_in_place(&challenge).unwrap();
+        assert_eq!(folded, prod_poly.partial_evaluate(0, &[challenge]).unwrap());
+        assert_eq!(folded.n_vars(), 2);
+
+        // the folded round polynomial at 0 and 1 sums to the previous round poly at r
+        let round_evaluations = folded.round_evaluations().unwrap();
+        assert_eq!(
+            round_evaluations[0] + round_evaluations[1],
+            prod_poly.sum_with_fixed_prefix(&[challenge]).unwrap()
+        );
+
+        folded.fold_in_place(&challenge).unwrap();
+        folded.fold_in_place(&challenge).unwrap();
+        assert!(folded.round_evaluations().is_err());
+        assert!(folded.fold_in_place(&challenge).is_err());
+    }
 }
@@ -0,0 +1,267 @@
+use crate::encoding::{self, Endianness};
+use crate::hypercube_sum::HypercubeSum;
+use crate::product_poly::ProductPoly;
+use crate::SumcheckPolynomial;
+use ark_ff::PrimeField;
+use std::io::Write;
+
+/// Weighted sum of product polynomials
+/// P(x) = w_1.P_1(x) + w_2.P_2(x) + ...
+/// used for random linear combinations (e.g. batching several sumcheck claims)
+/// without flattening every term into a single polynomial
+#[derive(Clone, Debug, PartialEq)]
+pub struct SumPoly<F: PrimeField> {
+    n_vars: usize,
+    terms: Vec<(F, ProductPoly<F>)>,
+}
+
+impl<F: PrimeField> SumPoly<F> {
+    /// Instantiate a new sum poly from (weight, product poly) terms
+    pub fn new(terms: Vec<(F, ProductPoly<F>)>) -> Result<Self, &'static str> {
+        if terms.is_empty() {
+            return Err("cannot create sum polynomial from empty terms");
+        }
+
+        let n_vars = terms[0].1.n_vars();
+        if terms.iter().any(|(_, term)| term.n_vars() != n_vars) {
+            return Err("cannot create sum polynomial from terms that don't share the same number of variables");
+        }
+
+        Ok(Self { n_vars, terms })
+    }
+
+    /// Return the number of variables
+    pub fn n_vars(&self) -> usize {
+        self.n_vars
+    }
+
+    /// Returns the (weight, product poly) terms
+    pub fn terms(&self) -> &[(F, ProductPoly<F>)] {
+        &self.terms
+    }
+
+    /// Returns the max degree of any single variable
+    /// the max over the terms, weights don't change the degree
+    pub fn max_variable_degree(&self) -> usize {
+        self.terms
+            .iter()
+            .map(|(_, term)| term.max_variable_degree())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Evaluate the sum poly at the given assignments
+    pub fn evaluate(&self, assignments: &[F]) -> Result<F, &'static str> {
+        self.terms
+            .iter()
+            .try_fold(F::zero(), |sum, (weight, term)| {
+                term.evaluate(assignments)
+                    .map(|value| sum + *weight * value)
+            })
+    }
+
+    /// Partially evaluate every term on the same input, returns a new sum poly
+    pub fn partial_evaluate(
+        &self,
+        initial_var: usize,
+        assignments: &[F],
+    ) -> Result<Self, &'static str> {
+        let terms = self
+            .terms
+            .iter()
+            .map(|(weight, term)| {
+                term.partial_evaluate(initial_var, assignments)
+                    .map(|term| (*weight, term))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::new(terms)
+    }
+
+    /// Returns the evaluations of the sum poly over the boolean hypercube
+    /// the weighted sum of each term's `prod_reduce`
+    pub fn reduce(&self) -> Vec<F> {
+        let mut result = vec![F::zero(); 1 << self.n_vars];
+        for (weight, term) in &self.terms {
+            for (sum, value) in result.iter_mut().zip(term.prod_reduce()) {
+                *sum += *weight * value;
+            }
+        }
+        result
+    }
+
+    /// Evaluates the round polynomial (first variable free, the rest summed over the hypercube)
+    /// at `partial_evaluation_points(max_variable_degree)`
+    pub fn round_evaluations(&self) -> Result<Vec<F>, &'static str> {
+        let degree = self.max_variable_degree();
+        let mut result = vec![F::zero(); degree + 1];
+        for (weight, term) in &self.terms {
+            let evaluations = term.round_evaluations_with_degree(degree)?;
+            for (sum, value) in result.iter_mut().zip(evaluations) {
+                *sum += *weight * value;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Fixes the first variable of every term to r in place
+    pub fn fold_in_place(&mut self, r: &F) -> Result<(), &'static str> {
+        for (_, term) in self.terms.iter_mut() {
+            term.fold_in_place(r)?;
+        }
+        self.n_vars = self.terms[0].1.n_vars();
+        Ok(())
+    }
+
+    /// Serialize the SumPoly
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with_endianness(Endianness::Big)
+    }
+
+    /// Serialize the SumPoly with the given byte order
+    /// each term is encoded as its weight followed by the product poly
+    pub fn to_bytes_with_endianness(&self, endianness: Endianness) -> Vec<u8> {
+        encoding::to_bytes_with(|bytes| self.write_bytes_with_endianness(bytes, endianness))
+    }
+
+    /// Streams the `to_bytes` encoding to a writer
+    pub fn write_bytes<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.write_bytes_with_endianness(writer, Endianness::Big)
+    }
+
+    /// Streams the `to_bytes_with_endianness` encoding to a writer
+    pub fn write_bytes_with_endianness<W: Write>(
+        &self,
+        writer: &mut W,
+        endianness: Endianness,
+    ) -> std::io::Result<()> {
+        encoding::write_polynomial_header(writer, b"sum", self.n_vars, endianness)?;
+        encoding::write_length(writer, self.terms.len(), endianness)?;
+        for (weight, term) in &self.terms {
+            encoding::write_field_element(writer, weight, endianness)?;
+            term.write_bytes_with_endianness(writer, endianness)?;
+        }
+        Ok(())
+    }
+}
+
+impl<F: PrimeField> HypercubeSum<F> for SumPoly<F> {
+    fn hypercube_size(&self) -> usize {
+        1 << self.n_vars
+    }
+
+    fn hypercube_evaluation(&self, index: usize) -> F {
+        self.terms
+            .iter()
+            .map(|(weight, term)| *weight * term.hypercube_evaluation(index))
+            .sum()
+    }
+}
+
+impl<F: PrimeField> SumcheckPolynomial<F> for SumPoly<F> {
+    fn n_vars(&self) -> usize {
+        self.n_vars()
+    }
+
+    fn max_variable_degree(&self) -> usize {
+        self.max_variable_degree()
+    }
+
+    fn round_evaluations(&self) -> Result<Vec<F>, &'static str> {
+        self.round_evaluations()
+    }
+
+    fn fold_in_place(&mut self, r: &F) -> Result<(), &'static str> {
+        self.fold_in_place(r)
+    }
+
+    fn write_transcript_bytes<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.write_bytes(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SumPoly;
+    use crate::hypercube_sum::HypercubeSum;
+    use crate::test_fields::random_product_poly;
+    use ark_bls12_381::Fr;
+
+    fn random_sum_poly() -> SumPoly<Fr> {
+        let mut rng = ark_std::test_rng();
+        SumPoly::new(vec![
+            (Fr::from(3), random_product_poly(3, 2, &mut rng)),
+            (Fr::from(5), random_product_poly(3, 1, &mut rng)),
+            (-Fr::from(2), random_product_poly(3, 3, &mut rng)),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_new_sum_poly() {
+        let mut rng = ark_std::test_rng();
+        assert!(SumPoly::<Fr>::new(vec![]).is_err());
+        assert!(SumPoly::new(vec![
+            (Fr::from(1), random_product_poly(2, 1, &mut rng)),
+            (Fr::from(1), random_product_poly(3, 1, &mut rng)),
+        ])
+        .is_err());
+
+        let poly = random_sum_poly();
+        assert_eq!(poly.n_vars(), 3);
+        assert_eq!(poly.max_variable_degree(), 3);
+    }
+
+    #[test]
+    fn test_evaluate_and_reduce() {
+        let poly = random_sum_poly();
+        let point = [Fr::from(2), Fr::from(7), Fr::from(11)];
+
+        let expected = poly
+            .terms()
+            .iter()
+            .map(|(weight, term)| *weight * term.evaluate(&point).unwrap())
+            .sum::<Fr>();
+        assert_eq!(poly.evaluate(&point).unwrap(), expected);
+
+        let partial = poly.partial_evaluate(0, &point[..1]).unwrap();
+        assert_eq!(partial.evaluate(&point[1..]).unwrap(), expected);
+
+        let reduced = poly.reduce();
+        for (index, value) in reduced.iter().enumerate() {
+            let bits = (0..3)
+                .rev()
+                .map(|bit| Fr::from(((index >> bit) & 1) as u64))
+                .collect::<Vec<_>>();
+            assert_eq!(poly.evaluate(&bits).unwrap(), *value);
+        }
+    }
+
+    #[test]
+    fn test_round_evaluations() {
+        let poly = random_sum_poly();
+        let round_evaluations = poly.round_evaluations().unwrap();
+        assert_eq!(round_evaluations.len(), poly.max_variable_degree() + 1);
+
+        // g(0) + g(1) is the sum over the full hypercube
+        assert_eq!(
+            round_evaluations[0] + round_evaluations[1],
+            poly.sum_over_boolean_hypercube()
+        );
+
+        // g(t) = sum over the remaining hypercube with the first variable fixed to t
+        for (t, evaluation) in round_evaluations.iter().enumerate() {
+            let mut folded = poly.clone();
+            folded.fold_in_place(&Fr::from(t as u64)).unwrap();
+            assert_eq!(folded.sum_over_boolean_hypercube(), *evaluation);
+        }
+    }
+
+    #[test]
+    fn test_weights_are_encoded() {
+        let term = random_product_poly::<Fr, _>(2, 2, &mut ark_std::test_rng());
+        let left = SumPoly::new(vec![(Fr::from(1), term.clone())]).unwrap();
+        let right = SumPoly::new(vec![(Fr::from(2), term)]).unwrap();
+        assert_ne!(left.to_bytes(), right.to_bytes());
+    }
+}
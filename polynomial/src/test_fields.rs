@@ -0,0 +1,106 @@
+//! Small prime fields for tests and examples (only built for tests or with `test-utils`)
+//! every field here is deterministic and tiny enough to check results by hand,
+//! none of them are suitable for real proofs (soundness error is at least 1 / |F|)
+//!
+//! - `F17`: modulus 17, generator 3 (two adicity 4)
+//! - `F97`: modulus 97, generator 5 (two adicity 5)
+//! - `StarkField`: modulus 3 * 2^30 + 1, generator 5 (two adicity 30)
+//! - `BabyBear`: modulus 2^31 - 2^27 + 1, generator 31 (two adicity 27)
+//!
+//! `random_mle` and `random_product_poly` build random polynomial fixtures over any field.
+
+use crate::multilinear::evaluation_form::MultiLinearPolynomial;
+use crate::product_poly::ProductPoly;
+use ark_ff::{Fp64, MontBackend, MontConfig, PrimeField};
+use ark_std::rand::Rng;
+
+#[derive(MontConfig)]
+#[modulus = "17"]
+#[generator = "3"]
+pub struct F17Config;
+pub type F17 = Fp64<MontBackend<F17Config, 1>>;
+
+#[derive(MontConfig)]
+#[modulus = "97"]
+#[generator = "5"]
+pub struct F97Config;
+pub type F97 = Fp64<MontBackend<F97Config, 1>>;
+
+#[derive(MontConfig)]
+#[modulus = "3221225473"]
+#[generator = "5"]
+pub struct StarkFieldConfig;
+pub type StarkField = Fp64<MontBackend<StarkFieldConfig, 1>>;
+
+#[derive(MontConfig)]
+#[modulus = "2013265921"]
+#[generator = "31"]
+pub struct BabyBearConfig;
+pub type BabyBear = Fp64<MontBackend<BabyBearConfig, 1>>;
+
+/// Converts signed integers to field elements (negative values wrap around the modulus)
+pub fn field_vec<F: PrimeField>(values: &[i64]) -> Vec<F> {
+    values.iter().map(|value| F::from(*value)).collect()
+}
+
+/// Multilinear polynomial with n_vars variables and random evaluations
+pub fn random_mle<F: PrimeField, R: Rng>(n_vars: usize, rng: &mut R) -> MultiLinearPolynomial<F> {
+    let evaluations = (0..1 << n_vars).map(|_| F::rand(rng)).collect();
+    MultiLinearPolynomial::new(n_vars, evaluations).expect("evaluations match n_vars")
+}
+
+/// Product of n_factors random multilinear polynomials with n_vars variables each
+pub fn random_product_poly<F: PrimeField, R: Rng>(
+    n_vars: usize,
+    n_factors: usize,
+    rng: &mut R,
+) -> ProductPoly<F> {
+    let factors = (0..n_factors).map(|_| random_mle(n_vars, rng)).collect();
+    ProductPoly::new(factors).expect("n_factors is at least 1")
+}
+
+/// Builds a vec of field elements from integer literals
+/// e.g. fq_vec![F17; 1, 2, -1] == vec![F17::from(1), F17::from(2), F17::from(16)]
+#[macro_export]
+macro_rules! fq_vec {
+    ($field:ty; $($value:expr),* $(,)?) => {
+        {
+            let values: Vec<$field> = vec![$(<$field>::from($value as i64)),*];
+            values
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{field_vec, BabyBear, StarkField, F17, F97};
+    use ark_ff::{FftField, PrimeField};
+
+    fn check_field<F: FftField + PrimeField>(modulus: u64, two_adicity: u32) {
+        assert_eq!(F::MODULUS.as_ref()[0], modulus);
+        assert_eq!(F::TWO_ADICITY, two_adicity);
+        // the generator has full order, so the largest power of two root is primitive
+        let root = F::TWO_ADIC_ROOT_OF_UNITY;
+        assert_eq!(root.pow([1 << two_adicity]), F::ONE);
+        assert_ne!(root.pow([1 << (two_adicity - 1)]), F::ONE);
+        assert_ne!(F::GENERATOR.pow([(modulus - 1) / 2]), F::ONE);
+    }
+
+    #[test]
+    fn test_fields() {
+        check_field::<F17>(17, 4);
+        check_field::<F97>(97, 5);
+        check_field::<StarkField>(3 * (1 << 30) + 1, 30);
+        check_field::<BabyBear>((1 << 31) - (1 << 27) + 1, 27);
+    }
+
+    #[test]
+    fn test_fq_vec() {
+        assert_eq!(
+            fq_vec![F17; 1, 2, -1],
+            vec![F17::from(1), F17::from(2), F17::from(16)]
+        );
+        assert_eq!(field_vec::<F97>(&[-1, 98]), fq_vec![F97; 96, 1]);
+        assert_eq!(fq_vec![F17;], Vec::<F17>::new());
+    }
+}
@@ -1,7 +1,10 @@
+use crate::encoding::{self, Endianness};
+use crate::fft::{fft_with, ifft_with, zero_pad, EvaluationDomain, FftError, FftPrecomputation};
 use crate::multilinear::coefficient_form::CoeffMultilinearPolynomial;
 use crate::Polynomial;
-use ark_ff::{BigInteger, PrimeField};
+use ark_ff::PrimeField;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::io::Write;
 use std::ops;
 
 #[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
@@ -22,8 +25,24 @@ impl<F: PrimeField> UnivariatePolynomial<F> {
         self.coefficients.as_slice()
     }
 
-    // TODO: implement method to simplify coefficients by truncation
-    //  e.g. [0, 2, 0, 0] is equivalent to [0, 2]
+    /// Removes zero high degree coefficients
+    /// e.g. [0, 2, 0, 0] is equivalent to [0, 2], the zero poly has no coefficients
+    pub fn truncate_leading_zeros(&mut self) {
+        self.coefficients.truncate(self.normalized_len());
+    }
+
+    /// Number of coefficients once zero high degree coefficients are dropped
+    fn normalized_len(&self) -> usize {
+        self.coefficients
+            .iter()
+            .rposition(|coeff| !coeff.is_zero())
+            .map_or(0, |position| position + 1)
+    }
+
+    /// Evaluate the polynomial at every element of the domain (using the fft)
+    pub fn evaluate_over_domain(&self, domain: &EvaluationDomain<F>) -> Vec<F> {
+        domain.evaluate(&self.coefficients)
+    }
 
     /// Evaluate polynomial at a given point x
     pub fn evaluate(&self, x: &F) -> F {
@@ -40,7 +59,8 @@ impl<F: PrimeField> UnivariatePolynomial<F> {
     }
 
     /// Interpolate a set of y values over the interpolating set [0, 1, 2, ...]
-    pub fn interpolate(ys: Vec<F>) -> Self {
+    /// errors if there are more y values than field elements
+    pub fn interpolate(ys: Vec<F>) -> Result<Self, &'static str> {
         let mut xs = vec![];
         for i in 0..ys.len() {
             xs.push(F::from(i as u64));
@@ -49,9 +69,12 @@ impl<F: PrimeField> UnivariatePolynomial<F> {
     }
 
     /// returns a new polynomial that interpolates all the given points
-    // TODO: prevent duplication in the x values (use a new type)
-    // TODO: use new type to prevent x and y from being of different lengths
-    pub fn interpolate_xy(xs: Vec<F>, ys: Vec<F>) -> Self {
+    /// errors if the lengths differ or an x value repeats
+    pub fn interpolate_xy(xs: Vec<F>, ys: Vec<F>) -> Result<Self, &'static str> {
+        if xs.len() != ys.len() {
+            return Err("interpolation requires as many x values as y values");
+        }
+
         let mut result = UnivariatePolynomial::new(vec![]);
 
         for (lagrange_basis_index, (x, y)) in xs.iter().zip(ys.iter()).enumerate() {
@@ -65,7 +88,9 @@ impl<F: PrimeField> UnivariatePolynomial<F> {
 
                 // numerator = x -xs[i] where i != lagrange_basis_index
                 let numerator = UnivariatePolynomial::new(vec![-(*x_value), F::from(1_u8)]);
-                let denominator = (*x - x_value).inverse().unwrap();
+                let denominator = (*x - x_value)
+                    .inverse()
+                    .ok_or("interpolation x values must be distinct")?;
 
                 lagrange_basis =
                     &lagrange_basis * &(&numerator * &UnivariatePolynomial::new(vec![denominator]));
@@ -76,7 +101,7 @@ impl<F: PrimeField> UnivariatePolynomial<F> {
             result = &result + &monomial;
         }
 
-        result
+        Ok(result)
     }
 
     /// return true if polynomial is a zero poly i.e p(..) = 0
@@ -125,7 +150,7 @@ impl<F: PrimeField> Polynomial<F> for UnivariatePolynomial<F> {
             return Err("partial evaluation selector should point to only 1 variable");
         }
 
-        if assignments[0].0[0] == true {
+        if assignments[0].0[0] {
             Ok(Self::new(vec![self.evaluate(assignments[0].1)]))
         } else {
             // TODO: get rid of this clone
@@ -141,12 +166,20 @@ impl<F: PrimeField> Polynomial<F> for UnivariatePolynomial<F> {
         Ok(self.clone())
     }
 
-    fn to_bytes(&self) -> Vec<u8> {
-        let mut result = vec![];
-        for coeff in self.coefficients() {
-            result.extend(coeff.into_bigint().to_bytes_be());
-        }
-        result
+    /// Serialize the polynomial
+    /// zero high degree coefficients are dropped first so equal polynomials encode the same,
+    /// the coefficient count prefix is then degree + 1 (0 for the zero poly)
+    fn write_bytes_with_endianness<W: Write>(
+        &self,
+        writer: &mut W,
+        endianness: Endianness,
+    ) -> std::io::Result<()> {
+        encoding::write_polynomial_header(writer, b"univariate", 1, endianness)?;
+        encoding::write_field_elements(
+            writer,
+            &self.coefficients[..self.normalized_len()],
+            endianness,
+        )
     }
 
     fn additive_identity() -> Self {
@@ -193,19 +226,58 @@ impl<F: PrimeField> ops::Mul for &UnivariatePolynomial<F> {
 
         // Given 2 polynomials A, B of degree a, b respectively
         // the product polynomial C = AB will have max degree of a + b
-        let product_max_degree = self.degree() + other.degree();
-
         // we need d + 1 element to represent a polynomial of degree d
-        let mut product_coefficients = vec![F::zero(); product_max_degree + 1];
-
-        for i in 0..=self.degree() {
-            for j in 0..=other.degree() {
-                product_coefficients[i + j] += self.coefficients[i] * other.coefficients[j];
+        let product_len = self.degree() + other.degree() + 1;
+
+        // large products go through the fft, unless the field has no subgroup big enough
+        if product_len >= FFT_MUL_THRESHOLD {
+            if let Ok(product_coefficients) =
+                fft_mul(&self.coefficients, &other.coefficients, product_len)
+            {
+                return UnivariatePolynomial::new(product_coefficients);
             }
         }
 
-        UnivariatePolynomial::new(product_coefficients)
+        UnivariatePolynomial::new(schoolbook_mul(
+            &self.coefficients,
+            &other.coefficients,
+            product_len,
+        ))
+    }
+}
+
+/// Product size (in coefficients) from which multiplication switches to the fft
+/// below this the O(n^2) schoolbook method is faster
+const FFT_MUL_THRESHOLD: usize = 64;
+
+/// O(n^2) coefficient wise multiplication
+fn schoolbook_mul<F: PrimeField>(a: &[F], b: &[F], product_len: usize) -> Vec<F> {
+    let mut product_coefficients = vec![F::zero(); product_len];
+    for (i, a_coeff) in a.iter().enumerate() {
+        for (j, b_coeff) in b.iter().enumerate() {
+            product_coefficients[i + j] += *a_coeff * b_coeff;
+        }
     }
+    product_coefficients
+}
+
+/// O(n log n) multiplication, evaluate both polys over a big enough subgroup,
+/// multiply point wise then interpolate back to coefficients
+fn fft_mul<F: PrimeField>(a: &[F], b: &[F], product_len: usize) -> Result<Vec<F>, FftError> {
+    let domain_size = product_len.next_power_of_two();
+    let precomputation = FftPrecomputation::cached(domain_size)?;
+    let a_evaluations = fft_with(&zero_pad(a, domain_size), Some(&precomputation))?;
+    let b_evaluations = fft_with(&zero_pad(b, domain_size), Some(&precomputation))?;
+
+    let product_evaluations = a_evaluations
+        .iter()
+        .zip(b_evaluations.iter())
+        .map(|(a, b)| *a * b)
+        .collect::<Vec<_>>();
+
+    let mut product_coefficients = ifft_with(&product_evaluations, Some(&precomputation))?;
+    product_coefficients.truncate(product_len);
+    Ok(product_coefficients)
 }
 
 impl<F: PrimeField> TryFrom<CoeffMultilinearPolynomial<F>> for UnivariatePolynomial<F> {
@@ -226,23 +298,29 @@ impl<F: PrimeField> TryFrom<CoeffMultilinearPolynomial<F>> for UnivariatePolynom
     }
 }
 
+#[cfg(feature = "serde")]
+impl<F: PrimeField> serde::Serialize for UnivariatePolynomial<F> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::ark_serde::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, F: PrimeField> serde::Deserialize<'de> for UnivariatePolynomial<F> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::ark_serde::deserialize(deserializer)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::UnivariatePolynomial;
     use crate::multilinear::coefficient_form::CoeffMultilinearPolynomial;
+    use crate::test_fields::{field_vec, F17 as Fq};
     use crate::Polynomial;
-    use ark_ff::MontConfig;
-    use ark_ff::{Fp64, MontBackend};
 
-    #[derive(MontConfig)]
-    #[modulus = "17"]
-    #[generator = "3"]
-    struct FqConfig;
-    type Fq = Fp64<MontBackend<FqConfig, 1>>;
-
-    // TODO: move this functionality into the polynomial struct
     fn fq_from_vec(values: Vec<i64>) -> Vec<Fq> {
-        values.into_iter().map(Fq::from).collect()
+        field_vec(&values)
     }
 
     fn poly_from_vec(coefficients: Vec<i64>) -> UnivariatePolynomial<Fq> {
@@ -318,12 +396,33 @@ mod tests {
         assert_eq!(p_mul_q, poly_from_vec(vec![12, 25, 18, 24, 12, 8]));
     }
 
+    #[test]
+    fn test_interpolation_errors() {
+        // mismatched lengths
+        assert!(UnivariatePolynomial::interpolate_xy(
+            fq_from_vec(vec![0, 1]),
+            fq_from_vec(vec![0, 2, 4])
+        )
+        .is_err());
+
+        // repeated x value
+        assert!(UnivariatePolynomial::interpolate_xy(
+            fq_from_vec(vec![1, 2, 1]),
+            fq_from_vec(vec![0, 2, 4])
+        )
+        .is_err());
+
+        // 18 x values wrap around in F17 (17 = 0)
+        assert!(UnivariatePolynomial::interpolate(fq_from_vec(vec![1; 18])).is_err());
+    }
+
     #[test]
     fn test_polynomial_interpolation() {
         // p = 2x
         // evaluations = [(0, 0), (1, 2)]
         let p =
-            UnivariatePolynomial::interpolate_xy(fq_from_vec(vec![0, 1]), fq_from_vec(vec![0, 2]));
+            UnivariatePolynomial::interpolate_xy(fq_from_vec(vec![0, 1]), fq_from_vec(vec![0, 2]))
+                .unwrap();
         assert_eq!(p, poly_from_vec(vec![0, 2]));
 
         // p = 2x^2 + 5
@@ -331,21 +430,24 @@ mod tests {
         let p = UnivariatePolynomial::interpolate_xy(
             fq_from_vec(vec![0, 1, 2]),
             fq_from_vec(vec![5, 7, 13]),
-        );
+        )
+        .unwrap();
         assert_eq!(p, poly_from_vec(vec![5, 0, 2]));
 
         // p = 8x^5 + 12x^4 + 7x^3 + 1x^2 + 8x + 12
         let p = UnivariatePolynomial::interpolate_xy(
             fq_from_vec(vec![0, 1, 3, 4, 5, 8]),
             fq_from_vec(vec![12, 48, 3150, 11772, 33452, 315020]),
-        );
+        )
+        .unwrap();
         assert_eq!(p, poly_from_vec(vec![12, 25, 18, 24, 12, 8]));
 
         // p = 5x^3 - 12x
         let p = UnivariatePolynomial::interpolate_xy(
             fq_from_vec(vec![5, 7, 9, 1]),
             fq_from_vec(vec![565, 1631, 3537, -7]),
-        );
+        )
+        .unwrap();
         assert_eq!(p, poly_from_vec(vec![0, -12, 0, 5]));
     }
 
@@ -379,7 +481,7 @@ mod tests {
 
         // should not be able to build uni poly from multilinear poly with 3 variables
         let uni_poly_result: Result<UnivariatePolynomial<_>, _> = p.clone().try_into();
-        assert_eq!(uni_poly_result.is_err(), true);
+        assert!(uni_poly_result.is_err());
 
         // partial evaluate b
         // p = 2a + 3c
@@ -389,7 +491,7 @@ mod tests {
 
         // should fail, 2 variables
         let uni_poly_result: Result<UnivariatePolynomial<_>, _> = p.clone().try_into();
-        assert_eq!(uni_poly_result.is_err(), true);
+        assert!(uni_poly_result.is_err());
 
         // Partial evaluate a
         // p = 2 + 3c
@@ -400,7 +502,7 @@ mod tests {
 
         // should be successful, p has just 1 variable
         let uni_poly_result: Result<UnivariatePolynomial<_>, _> = p.try_into();
-        assert_eq!(uni_poly_result.is_err(), false);
+        assert!(uni_poly_result.is_ok());
         let uni_poly = uni_poly_result.unwrap();
         assert_eq!(uni_poly, poly_from_vec(vec![2, 3]));
     }
@@ -411,7 +513,8 @@ mod tests {
         let p = UnivariatePolynomial::interpolate_xy(
             fq_from_vec(vec![5, 7, 9, 1]),
             fq_from_vec(vec![565, 1631, 3537, -7]),
-        );
+        )
+        .unwrap();
 
         // n_vars
         assert_eq!(p.n_vars(), 1);
@@ -437,4 +540,68 @@ mod tests {
             p_poly
         );
     }
+
+    #[test]
+    fn test_fft_multiplication() {
+        use super::{fft_mul, schoolbook_mul};
+        use ark_bls12_381::Fr;
+        use ark_ff::UniformRand;
+
+        let mut rng = ark_std::test_rng();
+        let a = (0..100).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let b = (0..41).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let expected = schoolbook_mul(&a, &b, 140);
+
+        assert_eq!(fft_mul(&a, &b, 140).unwrap(), expected);
+        assert_eq!(
+            &UnivariatePolynomial::new(a) * &UnivariatePolynomial::new(b),
+            UnivariatePolynomial::new(expected)
+        );
+
+        // the mod 17 field only has subgroups up to size 16, large products fall back to schoolbook
+        let a = UnivariatePolynomial::new(fq_from_vec((0..50).collect()));
+        let b = UnivariatePolynomial::new(fq_from_vec((0..30).collect()));
+        let product = &a * &b;
+        assert_eq!(product.coefficients().len(), 79);
+        assert_eq!(
+            product.evaluate(&Fq::from(3)),
+            a.evaluate(&Fq::from(3)) * b.evaluate(&Fq::from(3))
+        );
+    }
+
+    #[test]
+    fn test_truncate_leading_zeros() {
+        let mut poly = UnivariatePolynomial::new(fq_from_vec(vec![0, 2, 0, 0]));
+        poly.truncate_leading_zeros();
+        assert_eq!(poly.coefficients(), fq_from_vec(vec![0, 2]).as_slice());
+
+        let mut zero = UnivariatePolynomial::new(fq_from_vec(vec![0, 0]));
+        zero.truncate_leading_zeros();
+        assert!(zero.coefficients().is_empty());
+
+        // padded zero coefficients don't change the encoding
+        assert_eq!(
+            UnivariatePolynomial::new(fq_from_vec(vec![0, 2, 0, 0])).to_bytes(),
+            UnivariatePolynomial::new(fq_from_vec(vec![0, 2])).to_bytes()
+        );
+        assert_ne!(
+            UnivariatePolynomial::new(fq_from_vec(vec![0, 2])).to_bytes(),
+            UnivariatePolynomial::new(fq_from_vec(vec![2])).to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_evaluate_over_domain() {
+        use crate::fft::EvaluationDomain;
+
+        let poly = UnivariatePolynomial::new(fq_from_vec(vec![5, 2, 3, 7, 1, 9]));
+        for size in [2, 4, 8, 16] {
+            let domain = EvaluationDomain::<Fq>::new(size).unwrap();
+            let expected = domain
+                .elements()
+                .map(|x| poly.evaluate(&x))
+                .collect::<Vec<_>>();
+            assert_eq!(poly.evaluate_over_domain(&domain), expected);
+        }
+    }
 }
@@ -1,11 +1,24 @@
 use std::cell::RefCell;
+use std::sync::OnceLock;
 use std::time::Instant;
 
+#[cfg(feature = "tracing")]
+pub use tracing;
+
+pub mod memory;
+
 type TimedUnit = (&'static str, Instant);
 
 thread_local! {
-    pub static BLOCKS: RefCell<Vec<TimedUnit>> = RefCell::new(vec![]);
-    pub static TAB_COUNT: RefCell<usize> = RefCell::new(0);
+    pub static BLOCKS: RefCell<Vec<TimedUnit>> = const { RefCell::new(vec![]) };
+    pub static TAB_COUNT: RefCell<usize> = const { RefCell::new(0) };
+}
+
+/// Returns true if PERF_LOG is set to true
+/// the env var is only read once, the result is cached for later calls
+pub fn perf_log_enabled() -> bool {
+    static PERF_LOG: OnceLock<bool> = OnceLock::new();
+    *PERF_LOG.get_or_init(|| std::env::var("PERF_LOG") == Ok(String::from("true")))
 }
 
 /// Starts a timer and stores the timer description
@@ -13,7 +26,7 @@ thread_local! {
 macro_rules! start_timer {
     ($str:literal) => {
         // guard should only run when PERF_LOG is set to true
-        if std::env::var("PERF_LOG") == Ok(String::from("true")) {
+        if $crate::perf_log_enabled() {
             // create timed unit
             $crate::BLOCKS
                 .with(|blocks| blocks.borrow_mut().push(($str, std::time::Instant::now())));
@@ -34,7 +47,7 @@ macro_rules! start_timer {
 macro_rules! end_timer {
     () => {
         // guard should only run when PERF_LOG is set to true
-        if std::env::var("PERF_LOG") == Ok(String::from("true")) {
+        if $crate::perf_log_enabled() {
             let (description, start_time) = $crate::BLOCKS.with(|blocks| {
                 blocks.borrow_mut().pop().unwrap()
                 // println!("{} (end): {:?}", description, start_time.elapsed());
@@ -54,3 +67,30 @@ macro_rules! end_timer {
         }
     };
 }
+
+/// Enters a `tracing` span (info level) that is exited when the returned guard is dropped
+/// e.g. let _span = trace_span!("sumcheck::round", round = i);
+/// only emits spans when the `tracing` feature is enabled, otherwise it is a no-op
+#[cfg(feature = "tracing")]
+#[macro_export]
+macro_rules! trace_span {
+    ($name:literal $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::tracing::info_span!($name $(, $key = $value)*).entered()
+    };
+}
+
+/// Enters a `tracing` span (info level) that is exited when the returned guard is dropped
+/// e.g. let _span = trace_span!("sumcheck::round", round = i);
+/// only emits spans when the `tracing` feature is enabled, otherwise it is a no-op
+#[cfg(not(feature = "tracing"))]
+#[macro_export]
+macro_rules! trace_span {
+    ($name:literal $(, $key:ident = $value:expr)* $(,)?) => {{
+        $(let _ = &$value;)*
+        $crate::DisabledSpan
+    }};
+}
+
+/// Guard returned by `trace_span!` when the `tracing` feature is disabled
+#[cfg(not(feature = "tracing"))]
+pub struct DisabledSpan;
@@ -0,0 +1,368 @@
+//! Memory usage reporting
+//! `record_alloc_scope!` snapshots allocation counters and peak RSS when a scope starts and ends,
+//! the difference is added to a process wide report, one entry per scope name, that can be
+//! printed once proving is done. Scopes that run many times don't grow the report.
+//!
+//! Allocation counters are only available when the `alloc` feature is enabled and
+//! `CountingAllocator` is installed as the global allocator by the final binary:
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: stat::memory::CountingAllocator = stat::memory::CountingAllocator;
+//!
+//! Peak RSS is read from /proc/self/status, so it is only reported on linux.
+//! Counters are process wide, scopes running concurrently on other threads are included.
+
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+#[cfg(feature = "alloc")]
+pub use counting::CountingAllocator;
+
+/// Allocation counters at a point in time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// number of allocations made
+    pub allocations: u64,
+    /// total bytes requested across all allocations
+    pub allocated_bytes: u64,
+    /// bytes currently allocated
+    pub live_bytes: u64,
+    /// highest value live_bytes has reached
+    pub peak_live_bytes: u64,
+}
+
+/// Memory usage of a recorded scope, accumulated over every time it ran
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeRecord {
+    pub name: &'static str,
+    /// number of times the scope ended
+    pub count: u64,
+    /// allocations made and bytes requested inside the scope, summed over the runs,
+    /// live_bytes is taken at the end of the latest run and peak_live_bytes is the process peak
+    pub alloc: Option<AllocStats>,
+    /// highest peak RSS of the process at the end of a run
+    pub peak_rss_bytes: Option<u64>,
+}
+
+impl ScopeRecord {
+    /// Adds a later run of the same scope
+    fn merge(&mut self, run: ScopeRecord) {
+        self.count += run.count;
+        self.alloc = match (self.alloc, run.alloc) {
+            (Some(total), Some(run)) => Some(AllocStats {
+                allocations: total.allocations + run.allocations,
+                allocated_bytes: total.allocated_bytes + run.allocated_bytes,
+                live_bytes: run.live_bytes,
+                peak_live_bytes: total.peak_live_bytes.max(run.peak_live_bytes),
+            }),
+            (total, run) => total.or(run),
+        };
+        self.peak_rss_bytes = self.peak_rss_bytes.max(run.peak_rss_bytes);
+    }
+}
+
+static REPORT: Mutex<BTreeMap<&'static str, ScopeRecord>> = Mutex::new(BTreeMap::new());
+
+/// Returns the current allocation counters
+/// None if the `alloc` feature is disabled
+pub fn alloc_stats() -> Option<AllocStats> {
+    #[cfg(feature = "alloc")]
+    return Some(counting::stats());
+    #[cfg(not(feature = "alloc"))]
+    None
+}
+
+/// Returns the peak resident set size of the process in bytes
+/// None if it can't be determined on this platform
+pub fn peak_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kilobytes = line
+        .trim_start_matches("VmHWM:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Guardrail against runaway memory usage
+/// errors if the peak RSS of the process is above the limit, passes if it can't be determined
+pub fn check_peak_rss(limit_bytes: u64) -> Result<(), &'static str> {
+    match peak_rss_bytes() {
+        Some(peak) if peak > limit_bytes => Err("peak rss exceeded the configured limit"),
+        _ => Ok(()),
+    }
+}
+
+/// Guard returned by `record_alloc_scope!`
+/// the scope is added to the memory report when the guard is dropped
+pub struct AllocScope {
+    name: &'static str,
+    start: Option<AllocStats>,
+}
+
+impl AllocScope {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            start: alloc_stats(),
+        }
+    }
+}
+
+impl Drop for AllocScope {
+    fn drop(&mut self) {
+        let alloc = self
+            .start
+            .zip(alloc_stats())
+            .map(|(start, end)| AllocStats {
+                allocations: end.allocations - start.allocations,
+                allocated_bytes: end.allocated_bytes - start.allocated_bytes,
+                live_bytes: end.live_bytes,
+                peak_live_bytes: end.peak_live_bytes,
+            });
+        let record = ScopeRecord {
+            name: self.name,
+            count: 1,
+            alloc,
+            peak_rss_bytes: peak_rss_bytes(),
+        };
+        if let Ok(mut report) = REPORT.lock() {
+            match report.entry(self.name) {
+                Entry::Occupied(mut entry) => entry.get_mut().merge(record),
+                Entry::Vacant(entry) => {
+                    entry.insert(record);
+                }
+            }
+        }
+    }
+}
+
+/// Records the memory usage of the enclosing scope into the memory report
+/// e.g. let _mem = record_alloc_scope!("sumcheck::prove");
+/// only records when PERF_LOG is set to true
+#[macro_export]
+macro_rules! record_alloc_scope {
+    ($name:literal) => {
+        $crate::perf_log_enabled().then(|| $crate::memory::AllocScope::new($name))
+    };
+}
+
+/// Removes and returns every scope recorded so far, one record per scope name, sorted by name
+pub fn take_memory_report() -> Vec<ScopeRecord> {
+    REPORT
+        .lock()
+        .map(|mut report| std::mem::take(&mut *report).into_values().collect())
+        .unwrap_or_default()
+}
+
+/// Formats scope records as a table, one row per scope
+pub fn format_memory_report(records: &[ScopeRecord]) -> String {
+    let format_value = |value: Option<u64>| value.map_or("-".to_string(), |v| v.to_string());
+
+    let mut table = format!(
+        "{:<32} {:>8} {:>12} {:>16} {:>16} {:>16}\n",
+        "scope", "count", "allocs", "alloc bytes", "peak live bytes", "peak rss bytes"
+    );
+    for record in records {
+        table.push_str(&format!(
+            "{:<32} {:>8} {:>12} {:>16} {:>16} {:>16}\n",
+            record.name,
+            record.count,
+            format_value(record.alloc.map(|alloc| alloc.allocations)),
+            format_value(record.alloc.map(|alloc| alloc.allocated_bytes)),
+            format_value(record.alloc.map(|alloc| alloc.peak_live_bytes)),
+            format_value(record.peak_rss_bytes),
+        ));
+    }
+    table
+}
+
+/// Prints the memory report and clears it
+/// only prints when PERF_LOG is set to true
+pub fn print_memory_report() {
+    if crate::perf_log_enabled() {
+        let records = take_memory_report();
+        if !records.is_empty() {
+            println!("{}", format_memory_report(&records));
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod counting {
+    use super::AllocStats;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+    static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+    static LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+    static PEAK_LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+
+    /// Global allocator shim that counts allocations before forwarding to the system allocator
+    pub struct CountingAllocator;
+
+    fn record_alloc(size: usize) {
+        let size = size as u64;
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_LIVE_BYTES.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(size: usize) {
+        LIVE_BYTES.fetch_sub(size as u64, Ordering::Relaxed);
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            record_dealloc(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                record_dealloc(layout.size());
+                record_alloc(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    pub(super) fn stats() -> AllocStats {
+        AllocStats {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+            live_bytes: LIVE_BYTES.load(Ordering::Relaxed),
+            peak_live_bytes: PEAK_LIVE_BYTES.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_peak_rss, format_memory_report, AllocScope, AllocStats, ScopeRecord};
+
+    #[cfg(feature = "alloc")]
+    #[global_allocator]
+    static ALLOCATOR: super::CountingAllocator = super::CountingAllocator;
+
+    #[test]
+    fn test_alloc_scope() {
+        for _ in 0..3 {
+            let _scope = AllocScope::new("memory::test_alloc_scope");
+            let buffer = vec![0_u8; 4096];
+            assert_eq!(buffer.len(), 4096);
+        }
+
+        let report = super::take_memory_report();
+        let record = report
+            .iter()
+            .find(|record| record.name == "memory::test_alloc_scope")
+            .unwrap();
+        // the runs are folded into a single record
+        assert_eq!(record.count, 3);
+
+        #[cfg(feature = "alloc")]
+        {
+            let alloc = record.alloc.unwrap();
+            assert!(alloc.allocations >= 3);
+            assert!(alloc.allocated_bytes >= 3 * 4096);
+        }
+        #[cfg(not(feature = "alloc"))]
+        assert!(record.alloc.is_none());
+
+        #[cfg(target_os = "linux")]
+        assert!(record.peak_rss_bytes.unwrap() > 0);
+    }
+
+    #[test]
+    fn test_scope_record_merge() {
+        let run = |allocated_bytes, peak_live_bytes, peak_rss_bytes| ScopeRecord {
+            name: "sumcheck::round",
+            count: 1,
+            alloc: Some(AllocStats {
+                allocations: 1,
+                allocated_bytes,
+                live_bytes: allocated_bytes,
+                peak_live_bytes,
+            }),
+            peak_rss_bytes: Some(peak_rss_bytes),
+        };
+
+        let mut record = run(64, 128, 4096);
+        record.merge(run(32, 96, 8192));
+        assert_eq!(
+            record,
+            ScopeRecord {
+                name: "sumcheck::round",
+                count: 2,
+                alloc: Some(AllocStats {
+                    allocations: 2,
+                    allocated_bytes: 96,
+                    live_bytes: 32,
+                    peak_live_bytes: 128,
+                }),
+                peak_rss_bytes: Some(8192),
+            }
+        );
+    }
+
+    #[test]
+    fn test_peak_rss_guardrail() {
+        assert!(check_peak_rss(u64::MAX).is_ok());
+        #[cfg(target_os = "linux")]
+        assert!(check_peak_rss(0).is_err());
+    }
+
+    #[test]
+    fn test_format_memory_report() {
+        let records = [
+            ScopeRecord {
+                name: "sumcheck::prove",
+                count: 1,
+                alloc: Some(AllocStats {
+                    allocations: 3,
+                    allocated_bytes: 96,
+                    live_bytes: 32,
+                    peak_live_bytes: 64,
+                }),
+                peak_rss_bytes: Some(1024),
+            },
+            ScopeRecord {
+                name: "sumcheck::round",
+                count: 4,
+                alloc: None,
+                peak_rss_bytes: None,
+            },
+        ];
+
+        let table = format_memory_report(&records);
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("sumcheck::prove"));
+        assert!(lines[1].ends_with("1024"));
+        assert!(lines[2].ends_with('-'));
+    }
+}
@@ -0,0 +1,214 @@
+//! Reduce several evaluation claims on the same multilinear polynomial to a single claim
+//!
+//! Given claims W(r_i) = v_i for i in 0..k, both parties build the curve l(t) of degree k - 1
+//! through the points (l(i) = r_i). The prover sends q(t) = W(l(t)) as evaluations at
+//! t = 0, 1, ..., (k - 1) * n_vars, the verifier checks q(i) = v_i, samples r and is left with
+//! the single claim W(l(r)) = q(r).
+
+use ark_ff::PrimeField;
+use polynomial::encoding::{field_element_to_bytes, field_elements_to_bytes};
+use polynomial::multilinear::evaluation_form::MultiLinearPolynomial;
+use polynomial::univariate_poly::UnivariatePolynomial;
+use std::marker::PhantomData;
+use transcript::channel::InteractiveChannel;
+
+/// Claim that a multilinear polynomial evaluates to value at point
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvaluationClaim<F: PrimeField> {
+    pub point: Vec<F>,
+    pub value: F,
+}
+
+impl<F: PrimeField> EvaluationClaim<F> {
+    pub fn new(point: Vec<F>, value: F) -> Self {
+        Self { point, value }
+    }
+
+    /// Check the claim against the polynomial
+    pub fn verify(&self, poly: &MultiLinearPolynomial<F>) -> Result<bool, &'static str> {
+        Ok(poly.evaluate(&self.point)? == self.value)
+    }
+}
+
+/// Evaluations of the polynomial restricted to the curve through the claimed points
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregationProof<F: PrimeField> {
+    pub evaluations: Vec<F>,
+}
+
+/// Reduces multiple `EvaluationClaim`s to one using transcript randomness
+pub struct ClaimAggregator<F: PrimeField> {
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> ClaimAggregator<F> {
+    /// Generates the aggregation proof, returns it along with the aggregated claim
+    pub fn prove<C: InteractiveChannel>(
+        poly: &MultiLinearPolynomial<F>,
+        claims: &[EvaluationClaim<F>],
+        transcript: &mut C,
+    ) -> Result<(AggregationProof<F>, EvaluationClaim<F>), &'static str> {
+        let curve = curve_through_points(claims, poly.n_vars())?;
+
+        let points = (0..=curve_restriction_degree(claims.len(), poly.n_vars()))
+            .map(|t| evaluate_curve(&curve, &F::from(t as u64)))
+            .collect::<Vec<_>>();
+        let evaluations = poly.evaluate_batch(&points)?;
+
+        let claim = Self::reduce(claims, &curve, &evaluations, transcript)?;
+        Ok((AggregationProof { evaluations }, claim))
+    }
+
+    /// Verifies the aggregation proof, returns the aggregated claim
+    /// the caller is still responsible for checking that claim (e.g. direct evaluation or an opening)
+    pub fn verify<C: InteractiveChannel>(
+        claims: &[EvaluationClaim<F>],
+        proof: &AggregationProof<F>,
+        transcript: &mut C,
+    ) -> Result<EvaluationClaim<F>, &'static str> {
+        let n_vars = claims.first().map_or(0, |claim| claim.point.len());
+        let curve = curve_through_points(claims, n_vars)?;
+
+        if proof.evaluations.len() != curve_restriction_degree(claims.len(), n_vars) + 1 {
+            return Err("invalid aggregation proof: wrong number of evaluations");
+        }
+
+        // q(i) = v_i
+        if claims
+            .iter()
+            .zip(proof.evaluations.iter())
+            .any(|(claim, evaluation)| claim.value != *evaluation)
+        {
+            return Err("verifier check failed: aggregation proof doesn't match the claims");
+        }
+
+        Self::reduce(claims, &curve, &proof.evaluations, transcript)
+    }
+
+    /// Absorbs the claims and the restriction, then evaluates both at the sampled challenge
+    fn reduce<C: InteractiveChannel>(
+        claims: &[EvaluationClaim<F>],
+        curve: &[UnivariatePolynomial<F>],
+        evaluations: &[F],
+        transcript: &mut C,
+    ) -> Result<EvaluationClaim<F>, &'static str> {
+        for claim in claims {
+            transcript.append(field_elements_to_bytes(&claim.point).as_slice())?;
+            transcript.append(field_element_to_bytes(&claim.value).as_slice())?;
+        }
+        transcript.append(field_elements_to_bytes(evaluations).as_slice())?;
+
+        let challenge = transcript.challenge::<F>()?;
+        let restriction = UnivariatePolynomial::interpolate(evaluations.to_vec())?;
+
+        Ok(EvaluationClaim {
+            point: evaluate_curve(curve, &challenge),
+            value: restriction.evaluate(&challenge),
+        })
+    }
+}
+
+/// Degree of W(l(t)), W multilinear in n_vars and l of degree claim_count - 1
+/// at least claim_count - 1 so every claimed value is one of the sent evaluations
+fn curve_restriction_degree(claim_count: usize, n_vars: usize) -> usize {
+    (claim_count - 1) * n_vars.max(1)
+}
+
+/// Returns one polynomial per coordinate, l(i) = claims[i].point
+fn curve_through_points<F: PrimeField>(
+    claims: &[EvaluationClaim<F>],
+    n_vars: usize,
+) -> Result<Vec<UnivariatePolynomial<F>>, &'static str> {
+    if claims.is_empty() {
+        return Err("claim aggregation requires at least one claim");
+    }
+    if claims.iter().any(|claim| claim.point.len() != n_vars) {
+        return Err("every claim point must assign all variables");
+    }
+
+    (0..n_vars)
+        .map(|coordinate| {
+            UnivariatePolynomial::interpolate(
+                claims.iter().map(|claim| claim.point[coordinate]).collect(),
+            )
+        })
+        .collect()
+}
+
+fn evaluate_curve<F: PrimeField>(curve: &[UnivariatePolynomial<F>], t: &F) -> Vec<F> {
+    curve
+        .iter()
+        .map(|coordinate| coordinate.evaluate(t))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AggregationProof, ClaimAggregator, EvaluationClaim};
+    use ark_bls12_381::Fr;
+    use ark_ff::UniformRand;
+    use polynomial::multilinear::evaluation_form::MultiLinearPolynomial;
+    use polynomial::test_fields::random_mle;
+    use transcript::Transcript;
+
+    fn random_claims(poly: &MultiLinearPolynomial<Fr>, count: usize) -> Vec<EvaluationClaim<Fr>> {
+        let mut rng = ark_std::test_rng();
+        (0..count)
+            .map(|_| {
+                let point = (0..poly.n_vars())
+                    .map(|_| Fr::rand(&mut rng))
+                    .collect::<Vec<_>>();
+                let value = poly.evaluate(&point).unwrap();
+                EvaluationClaim::new(point, value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_aggregate_claims() {
+        let mut rng = ark_std::test_rng();
+        let poly = random_mle::<Fr, _>(3, &mut rng);
+
+        for count in 1..=3 {
+            let claims = random_claims(&poly, count);
+            let (proof, prover_claim) =
+                ClaimAggregator::prove(&poly, &claims, &mut Transcript::new()).unwrap();
+            assert_eq!(proof.evaluations.len(), (count - 1) * 3 + 1);
+
+            let verifier_claim =
+                ClaimAggregator::verify(&claims, &proof, &mut Transcript::new()).unwrap();
+            assert_eq!(prover_claim, verifier_claim);
+            assert!(verifier_claim.verify(&poly).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_aggregate_rejects_invalid_claims() {
+        let mut rng = ark_std::test_rng();
+        let poly = random_mle::<Fr, _>(2, &mut rng);
+        let claims = random_claims(&poly, 2);
+        let (proof, _) = ClaimAggregator::prove(&poly, &claims, &mut Transcript::new()).unwrap();
+
+        // a false claim can't be matched by the honest restriction
+        let mut false_claims = claims.clone();
+        false_claims[1].value += Fr::from(1);
+        assert!(ClaimAggregator::verify(&false_claims, &proof, &mut Transcript::new()).is_err());
+
+        // a restriction tampered to match the false claim fails the final evaluation check
+        let mut tampered = proof.clone();
+        tampered.evaluations[1] += Fr::from(1);
+        let claim =
+            ClaimAggregator::verify(&false_claims, &tampered, &mut Transcript::new()).unwrap();
+        assert!(!claim.verify(&poly).unwrap());
+
+        let short = AggregationProof {
+            evaluations: proof.evaluations[..2].to_vec(),
+        };
+        assert!(ClaimAggregator::verify(&claims, &short, &mut Transcript::new()).is_err());
+
+        let mut mismatched = claims.clone();
+        mismatched[0].point.pop();
+        assert!(ClaimAggregator::verify(&mismatched, &proof, &mut Transcript::new()).is_err());
+        assert!(ClaimAggregator::verify(&[], &proof, &mut Transcript::new()).is_err());
+    }
+}
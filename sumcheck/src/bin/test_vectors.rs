@@ -0,0 +1,13 @@
+//! Prints the sumcheck consistency vectors (over the BLS12-381 scalar field) as JSON
+//! see `sumcheck::test_vectors` for the fixture format and the challenge derivation
+
+use ark_bls12_381::Fr;
+use sumcheck::test_vectors::default_fixtures;
+
+fn main() {
+    let fixtures = default_fixtures::<Fr>().expect("default fixtures are valid polynomials");
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&fixtures).expect("fixtures serialize to json")
+    );
+}
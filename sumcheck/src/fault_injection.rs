@@ -0,0 +1,223 @@
+//! Malicious prover harness
+//! runs the honest prover over a channel that injects a single fault into the messages it
+//! sends and the challenges it gets back, every fault must lead the verifier to reject the proof.
+
+use crate::prover::SumcheckProver;
+use crate::verifier::SumcheckVerifier;
+use crate::{SecurityConfig, SumcheckOptions, SumcheckProof};
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use polynomial::encoding::{
+    field_element_to_bytes, field_elements_to_bytes, read_field_element, read_field_elements,
+    Endianness,
+};
+use polynomial::product_poly::ProductPoly;
+use polynomial::test_fields::random_product_poly;
+use transcript::channel::InteractiveChannel;
+use transcript::Transcript;
+
+/// Deviations from the honest prover, rounds are counted by the challenges requested
+#[derive(Clone, Copy, Debug)]
+enum Fault {
+    /// Behave honestly
+    None,
+    /// Add `delta` to the evaluation at `index` of the round poly sent in `round`
+    TamperRoundPoly {
+        round: usize,
+        index: usize,
+        delta: u64,
+    },
+    /// Sample the round challenge before absorbing the round poly
+    ReorderAbsorption { round: usize },
+    /// Reuse the previous challenge (zero in the first round) instead of sampling one
+    SkipChallenge { round: usize },
+    /// Drop the `message`-th append (the poly binding is sent first)
+    SkipAppend { message: usize },
+}
+
+/// Prover side channel injecting the fault into an honest run
+/// appends are held back until the next challenge request, with round tags on the
+/// last two held back messages are the round poly and its round tag
+struct FaultyChannel<F: PrimeField, C: InteractiveChannel> {
+    inner: C,
+    fault: Fault,
+    pending: Vec<Vec<u8>>,
+    appends: usize,
+    previous_challenge: Option<Vec<u8>>,
+    /// number of appends made before each challenge request
+    appends_before_challenge: Vec<usize>,
+    /// round and evaluations of the round poly sent in place of the honest one
+    tampered: Option<(usize, Vec<F>)>,
+}
+
+impl<F: PrimeField, C: InteractiveChannel> FaultyChannel<F, C> {
+    fn new(inner: C, fault: Fault) -> Self {
+        Self {
+            inner,
+            fault,
+            pending: vec![],
+            appends: 0,
+            previous_challenge: None,
+            appends_before_challenge: vec![],
+            tampered: None,
+        }
+    }
+
+    fn forward(&mut self, messages: Vec<Vec<u8>>) -> Result<(), &'static str> {
+        messages
+            .iter()
+            .try_for_each(|message| self.inner.append(message))
+    }
+}
+
+impl<F: PrimeField, C: InteractiveChannel> InteractiveChannel for FaultyChannel<F, C> {
+    fn append(&mut self, message: &[u8]) -> Result<(), &'static str> {
+        let index = self.appends;
+        self.appends += 1;
+        if !matches!(self.fault, Fault::SkipAppend { message } if message == index) {
+            self.pending.push(message.to_vec());
+        }
+        Ok(())
+    }
+
+    fn challenge<G: PrimeField>(&mut self) -> Result<G, &'static str> {
+        let round = self.appends_before_challenge.len();
+        self.appends_before_challenge.push(self.appends);
+        let mut pending = std::mem::take(&mut self.pending);
+        let round_poly = pending.len().checked_sub(2);
+
+        if let Fault::TamperRoundPoly {
+            round: fault_round,
+            index,
+            delta,
+        } = self.fault
+        {
+            if fault_round == round {
+                let round_poly = round_poly.ok_or("no round poly before the challenge")?;
+                let mut evaluations: Vec<F> =
+                    read_field_elements(&mut pending[round_poly].as_slice(), Endianness::Big)
+                        .map_err(|_| "round poly isn't a field element sequence")?;
+                evaluations[index] += F::from(delta);
+                pending[round_poly] = field_elements_to_bytes(&evaluations);
+                self.tampered = Some((round, evaluations));
+            }
+        }
+
+        let challenge = match self.fault {
+            Fault::ReorderAbsorption { round: fault_round } if fault_round == round => {
+                let round_poly =
+                    pending.remove(round_poly.ok_or("no round poly before the challenge")?);
+                self.forward(pending)?;
+                let challenge = self.inner.challenge()?;
+                self.inner.append(&round_poly)?;
+                challenge
+            }
+            Fault::SkipChallenge { round: fault_round } if fault_round == round => {
+                self.forward(pending)?;
+                match &self.previous_challenge {
+                    Some(bytes) => read_field_element(&mut bytes.as_slice(), Endianness::Big)
+                        .map_err(|_| "failed to decode the previous challenge")?,
+                    None => G::ZERO,
+                }
+            }
+            _ => {
+                self.forward(pending)?;
+                self.inner.challenge()?
+            }
+        };
+
+        self.previous_challenge = Some(field_element_to_bytes(&challenge));
+        Ok(challenge)
+    }
+}
+
+/// Runs the honest prover over a `FaultyChannel` with the given fault
+/// the proof records the round polys as sent, returns it with the number of appends made
+/// before each challenge
+fn prove_with_fault<F: PrimeField>(
+    poly: ProductPoly<F>,
+    sum: F,
+    fault: Fault,
+) -> Result<(SumcheckProof<F>, Vec<usize>), &'static str> {
+    let mut channel = FaultyChannel::new(Transcript::new(), fault);
+    let (mut proof, _) = SumcheckProver::prove_over_channel(
+        poly,
+        sum,
+        SumcheckOptions::full(&SecurityConfig::default()),
+        &mut channel,
+    )?;
+    if let Some((round, evaluations)) = channel.tampered {
+        proof.round_polys[round] = evaluations;
+    }
+    Ok((proof, channel.appends_before_challenge))
+}
+
+/// Verifier outcome, errors and failed final checks both count as rejection
+fn accepts<F: PrimeField>(poly: ProductPoly<F>, proof: SumcheckProof<F>) -> bool {
+    SumcheckVerifier::verify(poly, proof).unwrap_or(false)
+}
+
+/// Random product poly with its true sum
+fn random_claim(n_vars: usize, n_factors: usize) -> (ProductPoly<Fr>, Fr) {
+    let poly = random_product_poly(n_vars, n_factors, &mut ark_std::test_rng());
+    let sum = poly.sum_with_fixed_prefix(&[]).unwrap();
+    (poly, sum)
+}
+
+/// All single faults that change the proof for a poly with the given shape
+/// the last challenge is never used by the prover, and with a single variable the only
+/// round poly doesn't depend on any challenge, faults that only affect those leave the proof
+/// identical to the honest one (and the statement is true), so they are not included
+/// messages are skipped up to the request of the second to last challenge
+fn all_faults(n_vars: usize, degree: usize, appends_before_challenge: &[usize]) -> Vec<Fault> {
+    let mut faults = vec![];
+    if n_vars > 1 {
+        let messages = appends_before_challenge[n_vars - 2];
+        faults.extend((0..messages).map(|message| Fault::SkipAppend { message }));
+    }
+    for round in 0..n_vars {
+        for index in 0..=degree {
+            faults.push(Fault::TamperRoundPoly {
+                round,
+                index,
+                delta: 1,
+            });
+        }
+        if round + 1 < n_vars {
+            faults.push(Fault::ReorderAbsorption { round });
+            faults.push(Fault::SkipChallenge { round });
+        }
+    }
+    faults
+}
+
+#[test]
+fn test_harness_matches_honest_prover() {
+    let (poly, sum) = random_claim(3, 2);
+    let (proof, appends_before_challenge) =
+        prove_with_fault(poly.clone(), sum, Fault::None).unwrap();
+    assert_eq!(proof, SumcheckProver::prove(poly.clone(), sum).unwrap());
+    assert_eq!(appends_before_challenge.len(), 3);
+    assert!(accepts(poly, proof));
+}
+
+#[test]
+fn test_verifier_rejects_every_fault() {
+    for (n_vars, n_factors) in [(1, 1), (3, 1), (3, 2), (4, 3)] {
+        let (poly, sum) = random_claim(n_vars, n_factors);
+        let (_, appends_before_challenge) =
+            prove_with_fault(poly.clone(), sum, Fault::None).unwrap();
+        for fault in all_faults(
+            n_vars,
+            poly.max_variable_degree(),
+            &appends_before_challenge,
+        ) {
+            let (proof, _) = prove_with_fault(poly.clone(), sum, fault).unwrap();
+            assert!(
+                !accepts(poly.clone(), proof),
+                "verifier accepted a proof with fault {:?}",
+                fault
+            );
+        }
+    }
+}
@@ -1,15 +1,166 @@
+pub mod aggregation;
+#[cfg(test)]
+mod fault_injection;
+pub mod progress;
 pub mod prover;
+pub mod test_vectors;
 pub mod verifier;
 
-use ark_ff::{BigInteger, PrimeField};
+use crate::progress::ProgressObserver;
+use crate::prover::SumcheckProver;
+use crate::verifier::SumcheckVerifier;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use polynomial::encoding::{self, Endianness};
+use polynomial::product_poly::ProductPoly;
+use polynomial::SumcheckPolynomial;
+use std::marker::PhantomData;
+use transcript::channel::InteractiveChannel;
+use transcript::Transcript;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Holds the round polys and the initial prover claimed sum for sumcheck_old
 pub struct SumcheckProof<F: PrimeField> {
+    #[cfg_attr(feature = "serde", serde(with = "polynomial::ark_serde"))]
     sum: F,
+    /// max variable degree of the proven polynomial, each round poly has this degree
+    max_variable_degree: usize,
+    #[cfg_attr(feature = "serde", serde(with = "polynomial::ark_serde"))]
     round_polys: Vec<Vec<F>>,
 }
 
+impl<F: PrimeField> SumcheckProof<F> {
+    /// Serialize the proof
+    /// proving is deterministic, so the same poly and sum always give the same bytes
+    /// which makes the encoding suitable for golden fixtures
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes_with_endianness(Endianness::Big)
+    }
+
+    /// Serialize the proof with the given byte order
+    pub fn to_bytes_with_endianness(&self, endianness: Endianness) -> Vec<u8> {
+        encoding::to_bytes_with(|bytes| self.write_bytes_with_endianness(bytes, endianness))
+    }
+
+    /// Streams the `to_bytes` encoding to a writer (e.g. a file)
+    pub fn write_bytes<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.write_bytes_with_endianness(writer, Endianness::Big)
+    }
+
+    /// Streams the proof with the given byte order
+    /// the sum, the max variable degree, then the length prefixed round polys
+    pub fn write_bytes_with_endianness<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        endianness: Endianness,
+    ) -> std::io::Result<()> {
+        encoding::write_field_element(writer, &self.sum, endianness)?;
+        encoding::write_length(writer, self.max_variable_degree, endianness)?;
+        encoding::write_length(writer, self.round_polys.len(), endianness)?;
+        for round_poly in &self.round_polys {
+            encoding::write_field_elements(writer, round_poly, endianness)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the max variable degree the proof was generated for
+    pub fn max_variable_degree(&self) -> usize {
+        self.max_variable_degree
+    }
+
+    /// Deserialize a proof produced by `to_bytes`
+    /// field elements must be canonical and the whole input must be consumed
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Self::from_bytes_with_endianness(bytes, Endianness::Big)
+    }
+
+    /// Deserialize a proof produced by `to_bytes_with_endianness` with the same byte order
+    pub fn from_bytes_with_endianness(
+        bytes: &[u8],
+        endianness: Endianness,
+    ) -> Result<Self, &'static str> {
+        let mut reader = bytes;
+        let proof = Self::read_bytes_with_endianness(&mut reader, endianness)
+            .map_err(|_| "invalid sumcheck proof encoding")?;
+        if !reader.is_empty() {
+            return Err("invalid sumcheck proof encoding: trailing bytes");
+        }
+        Ok(proof)
+    }
+
+    /// Reads a proof streamed by `write_bytes` (e.g. from a file)
+    /// stops at the end of the proof, anything after it is left in the reader
+    pub fn read_bytes<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        Self::read_bytes_with_endianness(reader, Endianness::Big)
+    }
+
+    /// Reads a proof streamed by `write_bytes_with_endianness` with the same byte order
+    pub fn read_bytes_with_endianness<R: std::io::Read>(
+        reader: &mut R,
+        endianness: Endianness,
+    ) -> std::io::Result<Self> {
+        let sum = encoding::read_field_element(reader, endianness)?;
+        let max_variable_degree = encoding::read_length(reader, endianness)?;
+        let round_count = encoding::read_length(reader, endianness)?;
+        let mut round_polys = vec![];
+        for _ in 0..round_count {
+            round_polys.push(encoding::read_field_elements(reader, endianness)?);
+        }
+        Ok(Self {
+            sum,
+            max_variable_degree,
+            round_polys,
+        })
+    }
+
+    /// Checks the proof has the shape expected for a poly with n_vars variables and
+    /// variable degree at most degree_bound (when known), without touching the transcript
+    /// cheap enough to run on untrusted proofs before any verification work
+    pub fn check_structure(
+        &self,
+        n_vars: Option<usize>,
+        degree_bound: Option<usize>,
+        config: &SecurityConfig,
+    ) -> Result<(), &'static str> {
+        config.validate()?;
+
+        if degree_bound.is_some_and(|bound| self.max_variable_degree > bound) {
+            return Err("invalid proof: proof degree exceeds the degree bound");
+        }
+
+        // number of round_poly in the proof should match n_vars (for each repetition)
+        match n_vars {
+            Some(n_vars) => {
+                if Some(self.round_polys.len()) != n_vars.checked_mul(config.repetitions) {
+                    return Err("invalid proof: require 1 round poly for each variable in poly");
+                }
+            }
+            None => {
+                if !self.round_polys.len().is_multiple_of(config.repetitions) {
+                    return Err("invalid proof: round polys don't match the number of repetitions");
+                }
+            }
+        }
+
+        // each round poly is sent as evaluations at d + 1 points, d being the max variable degree
+        // the degree comes from the proof, so it can't be trusted not to overflow
+        let expected_evaluation_count = self
+            .max_variable_degree
+            .checked_add(1)
+            .ok_or("invalid proof: proof degree is too large")?;
+        if self
+            .round_polys
+            .iter()
+            .any(|round_poly| round_poly.len() != expected_evaluation_count)
+        {
+            return Err("invalid proof: round poly degree doesn't match the proof degree");
+        }
+
+        Ok(())
+    }
+}
+
 /// Sometimes the verifier doesn't want to perform the final check
 /// in such cases, a subclaim is returned, this subclaim has all information
 /// needed to verify the last check:
@@ -19,23 +170,336 @@ pub struct SubClaim<F: PrimeField> {
     challenges: Vec<F>,
 }
 
-/// Helper method for converting field elements to bytes
-fn field_elements_to_bytes<F: PrimeField>(field_elements: &[F]) -> Vec<u8> {
-    field_elements
-        .iter()
-        .map(|elem| elem.into_bigint().to_bytes_be())
-        .collect::<Vec<Vec<u8>>>()
-        .concat()
+impl<F: PrimeField> SubClaim<F> {
+    /// The value the initial poly must take at the challenges
+    pub fn sum(&self) -> F {
+        self.sum
+    }
+
+    /// The point the initial poly must be evaluated at
+    pub fn challenges(&self) -> &[F] {
+        &self.challenges
+    }
+}
+
+/// Binds the transcript to a commitment of the initial poly instead of the full poly
+/// the commitment can be any binding encoding (e.g. `hash_commitment` or a PCS commitment)
+fn append_commitment<C: InteractiveChannel>(
+    commitment: &[u8],
+    transcript: &mut C,
+) -> Result<(), &'static str> {
+    let mut bytes = b"sumcheck/commitment".to_vec();
+    encoding::append_length(&mut bytes, commitment.len(), Endianness::Big);
+    bytes.extend(commitment);
+    transcript.append(bytes.as_slice())
+}
+
+/// Application data a proof is bound to (e.g. a document hash or a public key)
+/// absorbed into the transcript before anything else, a proof made with one statement
+/// doesn't verify under any other
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Statement(Vec<u8>);
+
+impl Statement {
+    /// Statement over arbitrary bytes
+    pub fn new(bytes: impl Into<Vec<u8>>) -> Self {
+        Self(bytes.into())
+    }
+
+    /// Statement over field elements, uses the canonical field element encoding
+    pub fn from_field_elements<F: PrimeField>(elements: &[F]) -> Self {
+        Self(encoding::field_elements_to_bytes(elements))
+    }
+
+    /// Returns the statement bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Maps the statement to a single field element (Keccak256 reduced mod p)
+    /// for protocols that need the statement as a public input
+    pub fn to_field_element<F: PrimeField>(&self) -> F {
+        let mut transcript = Transcript::new();
+        transcript.append(b"sumcheck/statement");
+        transcript.append(&self.0);
+        transcript.sample_field_element()
+    }
+}
+
+/// Absorbs the statement, tagged and length prefixed so it can't run into the data after it
+fn append_statement<C: InteractiveChannel>(
+    statement: &Statement,
+    transcript: &mut C,
+) -> Result<(), &'static str> {
+    let mut bytes = b"sumcheck/statement".to_vec();
+    encoding::append_length(&mut bytes, statement.0.len(), Endianness::Big);
+    bytes.extend(&statement.0);
+    transcript.append(bytes.as_slice())
+}
+
+/// Keccak256 hash of the poly encoding, a short binding commitment to the poly
+/// unlike a PCS commitment, checking the final subclaim still requires the full poly
+pub fn hash_commitment<F: PrimeField>(poly: &ProductPoly<F>) -> Result<[u8; 32], &'static str> {
+    let mut transcript = Transcript::new();
+    poly.write_bytes(&mut transcript)
+        .map_err(|_| "failed to hash the polynomial")?;
+    Ok(transcript.sample_challenge())
+}
+
+/// What the transcript binds the proof to, absorbed after the statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PolyBinding<'a> {
+    /// Nothing, the verifier checks the returned subclaim itself (partial proofs)
+    #[default]
+    None,
+    /// The full poly encoding, the verifier needs the poly
+    Poly,
+    /// A commitment to the poly (e.g. `hash_commitment` or a PCS commitment), the returned
+    /// subclaim must be checked against the committed poly (e.g. with a PCS opening)
+    Commitment(&'a [u8]),
+}
+
+/// Options shared by the prover and the verifier
+/// a proof only verifies under the config, statement and binding it was generated with
+#[derive(Default)]
+pub struct SumcheckOptions<'a> {
+    pub config: SecurityConfig,
+    /// application data the proof is bound to, absorbed before anything else
+    pub statement: Option<&'a Statement>,
+    pub binding: PolyBinding<'a>,
+    /// receives the prover's progress, ignored by the verifier
+    pub observer: Option<&'a mut dyn ProgressObserver>,
+}
+
+impl SumcheckOptions<'_> {
+    /// Options binding the proof to the full poly
+    pub fn full(config: &SecurityConfig) -> Self {
+        Self {
+            config: config.clone(),
+            binding: PolyBinding::Poly,
+            ..Default::default()
+        }
+    }
+
+    /// Options for a partial proof (nothing bound)
+    pub fn partial(config: &SecurityConfig) -> Self {
+        Self {
+            config: config.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Absorbs the statement then the binding
+    fn append_to_transcript<F: PrimeField, P: SumcheckPolynomial<F>, C: InteractiveChannel>(
+        &self,
+        poly: Option<&P>,
+        transcript: &mut C,
+    ) -> Result<(), &'static str> {
+        if let Some(statement) = self.statement {
+            append_statement(statement, transcript)?;
+        }
+        match self.binding {
+            PolyBinding::None => Ok(()),
+            PolyBinding::Poly => poly
+                .ok_or("binding to the full poly requires the poly")?
+                .write_transcript_bytes(&mut ChannelWriter(transcript))
+                .map_err(|_| "failed to absorb the polynomial"),
+            PolyBinding::Commitment(commitment) => append_commitment(commitment, transcript),
+        }
+    }
+}
+
+/// Streams writes into a channel, each write is appended as it comes
+struct ChannelWriter<'a, C: InteractiveChannel>(&'a mut C);
+
+impl<C: InteractiveChannel> std::io::Write for ChannelWriter<'_, C> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.append(buf).map_err(std::io::Error::other)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Soundness parameters for a sumcheck proof
+/// for small fields a single run has non-negligible soundness error (n_vars * degree / |F|)
+/// running independent repetitions drives the error down to (that error) ^ repetitions
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecurityConfig {
+    /// number of parallel repetitions, all of them must pass for the proof to be accepted
+    pub repetitions: usize,
+    /// absorb an explicit tag ("sumcheck/round/<round>") before every challenge
+    /// on by default, turn off to verify proofs generated without tags
+    #[cfg_attr(feature = "serde", serde(default = "default_round_tags"))]
+    pub round_tags: bool,
+}
+
+#[cfg(feature = "serde")]
+fn default_round_tags() -> bool {
+    true
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            repetitions: 1,
+            round_tags: true,
+        }
+    }
+}
+
+impl SecurityConfig {
+    /// Ensures the config describes a runnable proof
+    fn validate(&self) -> Result<(), &'static str> {
+        if self.repetitions == 0 {
+            return Err("security config requires at least 1 repetition");
+        }
+        Ok(())
+    }
+
+    /// Domain separates proofs with different repetition counts
+    /// the single repetition transcript is left unchanged so existing proofs still verify
+    fn append_to_transcript<C: InteractiveChannel>(
+        &self,
+        transcript: &mut C,
+    ) -> Result<(), &'static str> {
+        if self.repetitions > 1 {
+            let mut bytes = b"sumcheck/repetitions".to_vec();
+            encoding::append_length(&mut bytes, self.repetitions, Endianness::Big);
+            transcript.append(bytes.as_slice())?;
+        }
+        if self.round_tags {
+            transcript.append(b"sumcheck/round-tags")?;
+        }
+        Ok(())
+    }
+
+    /// Absorbs the tag of the challenge about to be sampled (when round tags are enabled)
+    /// "sumcheck/round/<round>", with "/repetition/<repetition>" appended for repeated proofs
+    fn append_round_tag<C: InteractiveChannel>(
+        &self,
+        round: usize,
+        repetition: usize,
+        transcript: &mut C,
+    ) -> Result<(), &'static str> {
+        if !self.round_tags {
+            return Ok(());
+        }
+
+        let tag = if self.repetitions > 1 {
+            format!("sumcheck/round/{round}/repetition/{repetition}")
+        } else {
+            format!("sumcheck/round/{round}")
+        };
+        transcript.append(tag.as_bytes())
+    }
+}
+
+/// Common interface for sumcheck implementations
+/// protocols built on top of sumcheck should be generic over this
+/// so the underlying prover can be swapped without changing callers
+/// (the `_with_options` methods cover statements, repetitions and commitment binding)
+pub trait SumcheckScheme<F: PrimeField> {
+    /// The polynomial the sum is computed over
+    type Polynomial;
+    /// The proof produced by the prover
+    type Proof;
+
+    /// Generates a proof that the poly sums to `sum` over the boolean hypercube
+    fn prove(poly: Self::Polynomial, sum: F) -> Result<Self::Proof, &'static str>;
+
+    /// Generates a proof for a verifier without access to the poly,
+    /// also returns the challenges used
+    fn prove_partial(poly: Self::Polynomial, sum: F)
+        -> Result<(Self::Proof, Vec<F>), &'static str>;
+
+    /// Verifies a proof, including the final oracle check against the poly
+    fn verify(poly: Self::Polynomial, proof: Self::Proof) -> Result<bool, &'static str>;
+
+    /// Verifies a proof up to the final check, returns the subclaim for that check
+    fn verify_partial(proof: Self::Proof) -> Result<SubClaim<F>, &'static str>;
+
+    /// Generates a proof as described by the options, returns the challenges of each repetition
+    fn prove_with_options(
+        poly: Self::Polynomial,
+        sum: F,
+        options: SumcheckOptions,
+    ) -> Result<(Self::Proof, Vec<Vec<F>>), &'static str>;
+
+    /// Verifies a proof generated with the same options up to the final check
+    /// returns the subclaim of each repetition
+    fn verify_partial_with_options(
+        proof: Self::Proof,
+        options: &SumcheckOptions,
+    ) -> Result<Vec<SubClaim<F>>, &'static str>;
+}
+
+/// `SumcheckScheme` implementation backed by `SumcheckProver` and `SumcheckVerifier`
+pub struct Sumcheck<F: PrimeField> {
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> SumcheckScheme<F> for Sumcheck<F> {
+    type Polynomial = ProductPoly<F>;
+    type Proof = SumcheckProof<F>;
+
+    fn prove(poly: ProductPoly<F>, sum: F) -> Result<SumcheckProof<F>, &'static str> {
+        SumcheckProver::prove(poly, sum)
+    }
+
+    fn prove_partial(
+        poly: ProductPoly<F>,
+        sum: F,
+    ) -> Result<(SumcheckProof<F>, Vec<F>), &'static str> {
+        SumcheckProver::prove_partial(poly, sum)
+    }
+
+    fn verify(poly: ProductPoly<F>, proof: SumcheckProof<F>) -> Result<bool, &'static str> {
+        SumcheckVerifier::verify(poly, proof)
+    }
+
+    fn verify_partial(proof: SumcheckProof<F>) -> Result<SubClaim<F>, &'static str> {
+        SumcheckVerifier::verify_partial(proof)
+    }
+
+    fn prove_with_options(
+        poly: ProductPoly<F>,
+        sum: F,
+        options: SumcheckOptions,
+    ) -> Result<(SumcheckProof<F>, Vec<Vec<F>>), &'static str> {
+        SumcheckProver::prove_with_options(poly, sum, options)
+    }
+
+    fn verify_partial_with_options(
+        proof: SumcheckProof<F>,
+        options: &SumcheckOptions,
+    ) -> Result<Vec<SubClaim<F>>, &'static str> {
+        SumcheckVerifier::verify_partial_with_options(proof, options)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::prover::SumcheckProver;
     use crate::verifier::SumcheckVerifier;
+    use crate::{
+        hash_commitment, PolyBinding, SecurityConfig, Statement, SubClaim, Sumcheck,
+        SumcheckOptions, SumcheckProof, SumcheckScheme,
+    };
     use ark_bls12_381::Fr;
+    use ark_ff::Field;
+    use polynomial::encoding::{field_element_to_bytes, field_elements_to_bytes, Endianness};
+    use polynomial::hypercube_sum::HypercubeSum;
     use polynomial::multilinear::coefficient_form::CoeffMultilinearPolynomial;
     use polynomial::multilinear::evaluation_form::MultiLinearPolynomial;
+    use polynomial::oracle_poly::OraclePoly;
     use polynomial::product_poly::ProductPoly;
+    use std::thread;
+    use transcript::channel::message_channel;
+    use transcript::Transcript;
 
     fn p_2ab_3bc() -> MultiLinearPolynomial<Fr> {
         let evaluations = CoeffMultilinearPolynomial::new(
@@ -55,7 +519,7 @@ mod tests {
         // p = 2ab + 3bc
         let p = p_2ab_3bc();
         let prod_poly = ProductPoly::new(vec![p]).unwrap();
-        let proof = SumcheckProver::<1, Fr>::prove(prod_poly.clone(), Fr::from(10)).unwrap();
+        let proof = SumcheckProver::prove(prod_poly.clone(), Fr::from(10)).unwrap();
         let verification_result =
             SumcheckVerifier::verify(prod_poly, proof).expect("proof is invalid");
         assert!(verification_result);
@@ -96,7 +560,20 @@ mod tests {
 
         let p = ProductPoly::new(vec![p1, p2]).unwrap();
 
-        let proof = SumcheckProver::<2, Fr>::prove(p.clone(), Fr::from(5)).unwrap();
+        let proof = SumcheckProver::prove(p.clone(), Fr::from(5)).unwrap();
+        let verification_result = SumcheckVerifier::verify(p, proof).expect("proof is invalid");
+        assert!(verification_result);
+    }
+
+    #[test]
+    fn test_correct_sum_multivariate_deg_3() {
+        // p = (2ab + 3bc) . (2ab + 3bc) . (2ab + 3bc)
+        // the round poly degree is inferred from the number of factors
+        let p = ProductPoly::new(vec![p_2ab_3bc(), p_2ab_3bc(), p_2ab_3bc()]).unwrap();
+        assert_eq!(p.max_variable_degree(), 3);
+
+        // sum over the hypercube = 3^3 + 2^3 + 5^3 = 160
+        let proof = SumcheckProver::prove(p.clone(), Fr::from(160)).unwrap();
         let verification_result = SumcheckVerifier::verify(p, proof).expect("proof is invalid");
         assert!(verification_result);
     }
@@ -105,19 +582,531 @@ mod tests {
     fn test_correct_sum_prove_partial() {
         let p = p_2ab_3bc();
         let prod_poly = ProductPoly::new(vec![p]).unwrap();
-        let (proof, _) =
-            SumcheckProver::<1, Fr>::prove_partial(prod_poly.clone(), Fr::from(10)).unwrap();
+        let (proof, _) = SumcheckProver::prove_partial(prod_poly.clone(), Fr::from(10)).unwrap();
         let subclaim = SumcheckVerifier::verify_partial(proof).expect("proof is invalid");
         let expected_sum = prod_poly.evaluate(subclaim.challenges.as_slice()).unwrap();
         assert_eq!(expected_sum, subclaim.sum);
     }
 
+    #[test]
+    fn test_sumcheck_over_oracle() {
+        // P(x) = eq(r, x) . (2ab + 3bc)^2, never materialized as a table
+        let r = [Fr::from(3), Fr::from(7), Fr::from(11)];
+        let oracle = move |x: &[Fr]| {
+            let eq = r
+                .iter()
+                .zip(x)
+                .map(|(r, x)| *r * x + (Fr::ONE - r) * (Fr::ONE - x))
+                .product::<Fr>();
+            let p = Fr::from(2) * x[0] * x[1] + Fr::from(3) * x[1] * x[2];
+            eq * p * p
+        };
+
+        let poly = OraclePoly::new(3, 3, oracle);
+        let sum = poly.sum_over_boolean_hypercube();
+
+        let (proof, challenges) = SumcheckProver::prove_partial(poly.clone(), sum).unwrap();
+        let subclaim = SumcheckVerifier::verify_partial(proof).unwrap();
+        assert_eq!(subclaim.challenges, challenges);
+        assert_eq!(oracle(&challenges), subclaim.sum);
+
+        let (proof, _) = SumcheckProver::prove_partial(poly.clone(), sum + Fr::ONE).unwrap();
+        assert!(SumcheckVerifier::verify_partial(proof).is_err());
+
+        // an oracle has no encoding to bind the transcript to
+        assert!(SumcheckProver::prove(poly, sum).is_err());
+    }
+
     #[test]
     fn test_invalid_sum() {
         // p = 2ab + 3bc
         let p = p_2ab_3bc();
         let prod_poly = ProductPoly::new(vec![p]).unwrap();
-        let proof = SumcheckProver::<1, Fr>::prove(prod_poly.clone(), Fr::from(12)).unwrap();
+        let proof = SumcheckProver::prove(prod_poly.clone(), Fr::from(12)).unwrap();
         assert!(SumcheckVerifier::verify(prod_poly, proof).is_err());
     }
+
+    #[test]
+    fn test_proof_degree_checked() {
+        let p = p_2ab_3bc();
+        let prod_poly = ProductPoly::new(vec![p.clone(), p]).unwrap();
+        let sum = prod_poly.sum_with_fixed_prefix(&[]).unwrap();
+        let proof = SumcheckProver::prove(prod_poly.clone(), sum).unwrap();
+        assert_eq!(proof.max_variable_degree(), 2);
+        assert!(SumcheckVerifier::verify(prod_poly.clone(), proof.clone()).unwrap());
+
+        // round polys must have exactly degree + 1 evaluations, even without the poly
+        let mut tampered = proof.clone();
+        tampered.round_polys[1].push(Fr::from(0));
+        assert!(SumcheckVerifier::verify_partial(tampered).is_err());
+
+        // the proof degree must match the poly degree
+        let mut tampered = proof;
+        tampered.max_variable_degree = 3;
+        tampered
+            .round_polys
+            .iter_mut()
+            .for_each(|p| p.push(Fr::from(0)));
+        assert!(SumcheckVerifier::verify(prod_poly, tampered).is_err());
+    }
+
+    #[test]
+    fn test_proof_structure_checked() {
+        let prod_poly = ProductPoly::new(vec![p_2ab_3bc(), p_2ab_3bc()]).unwrap();
+        let sum = prod_poly.sum_with_fixed_prefix(&[]).unwrap();
+        let (proof, _) = SumcheckProver::prove_partial(prod_poly, sum).unwrap();
+        let config = SecurityConfig::default();
+        assert!(proof.check_structure(Some(3), Some(2), &config).is_ok());
+        assert!(SumcheckVerifier::verify_partial_bounded(proof.clone(), 3, 2).is_ok());
+
+        // wrong number of rounds, or a degree above the bound
+        assert!(proof.check_structure(Some(4), Some(2), &config).is_err());
+        assert!(SumcheckVerifier::verify_partial_bounded(proof.clone(), 2, 2).is_err());
+        assert!(SumcheckVerifier::verify_partial_bounded(proof.clone(), 3, 1).is_err());
+
+        // a dropped round is caught by the bounded verifier
+        let mut truncated = proof.clone();
+        truncated.round_polys.pop();
+        assert!(SumcheckVerifier::verify_partial_bounded(truncated, 3, 2).is_err());
+
+        // an untrusted degree can't overflow the expected round poly length
+        let mut oversized = proof.clone();
+        oversized.max_variable_degree = usize::MAX;
+        assert!(oversized.check_structure(None, None, &config).is_err());
+        assert!(SumcheckVerifier::verify_partial_bounded(oversized.clone(), 3, 2).is_err());
+        assert!(SumcheckVerifier::verify_partial(oversized.clone()).is_err());
+        let doubled = ProductPoly::new(vec![p_2ab_3bc(), p_2ab_3bc()]).unwrap();
+        assert!(SumcheckVerifier::verify(doubled.clone(), oversized.clone()).is_err());
+        assert!(SumcheckVerifier::verify_over_channel(
+            oversized,
+            Some(&doubled),
+            &SumcheckOptions::partial(&config),
+            &mut Transcript::new(),
+        )
+        .is_err());
+
+        // the encoding must be consumed exactly
+        let mut bytes = proof.to_bytes();
+        assert_eq!(SumcheckProof::<Fr>::from_bytes(&bytes).unwrap(), proof);
+        bytes.push(0);
+        assert!(SumcheckProof::<Fr>::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_repeated_sumcheck() {
+        let prod_poly = ProductPoly::new(vec![p_2ab_3bc()]).unwrap();
+        let config = SecurityConfig {
+            repetitions: 3,
+            ..Default::default()
+        };
+
+        let proof =
+            SumcheckProver::prove_with_config(prod_poly.clone(), Fr::from(10), &config).unwrap();
+        assert_eq!(proof.round_polys.len(), 3 * prod_poly.n_vars());
+        assert!(
+            SumcheckVerifier::verify_with_config(prod_poly.clone(), proof.clone(), &config)
+                .unwrap()
+        );
+
+        // a proof for a different number of repetitions is rejected
+        assert!(SumcheckVerifier::verify(prod_poly.clone(), proof).is_err());
+        assert!(SumcheckVerifier::verify_with_config(
+            prod_poly.clone(),
+            SumcheckProver::prove(prod_poly.clone(), Fr::from(10)).unwrap(),
+            &config
+        )
+        .is_err());
+
+        // wrong sum is rejected
+        let proof =
+            SumcheckProver::prove_with_config(prod_poly.clone(), Fr::from(12), &config).unwrap();
+        assert!(SumcheckVerifier::verify_with_config(prod_poly.clone(), proof, &config).is_err());
+
+        // zero repetitions is not a valid config
+        assert!(SumcheckProver::prove_with_config(
+            prod_poly,
+            Fr::from(10),
+            &SecurityConfig {
+                repetitions: 0,
+                ..Default::default()
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_sumcheck_with_round_tags() {
+        let prod_poly = ProductPoly::new(vec![p_2ab_3bc()]).unwrap();
+        // tags are on by default
+        let tagged = SecurityConfig::default();
+        assert!(tagged.round_tags);
+        let untagged = SecurityConfig {
+            round_tags: false,
+            ..Default::default()
+        };
+
+        let proof =
+            SumcheckProver::prove_with_config(prod_poly.clone(), Fr::from(10), &untagged).unwrap();
+        assert!(
+            SumcheckVerifier::verify_with_config(prod_poly.clone(), proof.clone(), &untagged)
+                .unwrap()
+        );
+        let rejected = |result: Result<bool, &'static str>| !matches!(result, Ok(true));
+        assert!(rejected(SumcheckVerifier::verify(prod_poly.clone(), proof)));
+
+        // first challenge follows the written derivation:
+        // round-tags marker, sum, round 0 poly, "sumcheck/round/0", then sample
+        let (proof, challenges) =
+            SumcheckProver::prove_partial(prod_poly.clone(), Fr::from(10)).unwrap();
+        let mut transcript = Transcript::new();
+        transcript.append(b"sumcheck/round-tags");
+        transcript.append(&field_element_to_bytes(&Fr::from(10)));
+        transcript.append(&field_elements_to_bytes(&proof.round_polys[0]));
+        transcript.append(b"sumcheck/round/0");
+        assert_eq!(challenges[0], transcript.sample_field_element::<Fr>());
+
+        // repetitions are tagged separately and still verify
+        let config = SecurityConfig {
+            repetitions: 2,
+            ..Default::default()
+        };
+        let proof =
+            SumcheckProver::prove_with_config(prod_poly.clone(), Fr::from(10), &config).unwrap();
+        assert!(SumcheckVerifier::verify_with_config(prod_poly, proof, &config).unwrap());
+    }
+
+    #[test]
+    fn test_repeated_sumcheck_partial() {
+        let prod_poly = ProductPoly::new(vec![p_2ab_3bc()]).unwrap();
+        let config = SecurityConfig {
+            repetitions: 2,
+            ..Default::default()
+        };
+
+        let (proof, challenges) =
+            SumcheckProver::prove_partial_with_config(prod_poly.clone(), Fr::from(10), &config)
+                .unwrap();
+        let subclaims = SumcheckVerifier::verify_partial_with_config(proof, &config).unwrap();
+
+        assert_eq!(subclaims.len(), 2);
+        // each repetition samples its own challenges
+        assert_ne!(challenges[0], challenges[1]);
+        for (subclaim, challenges) in subclaims.iter().zip(challenges.iter()) {
+            assert_eq!(&subclaim.challenges, challenges);
+            assert_eq!(prod_poly.evaluate(challenges).unwrap(), subclaim.sum);
+        }
+    }
+
+    #[test]
+    fn test_sumcheck_with_commitment() {
+        let prod_poly = ProductPoly::new(vec![p_2ab_3bc()]).unwrap();
+        let commitment = hash_commitment(&prod_poly).unwrap();
+        let bound_to = |commitment| SumcheckOptions {
+            binding: PolyBinding::Commitment(commitment),
+            ..Default::default()
+        };
+
+        let (proof, challenges) = SumcheckProver::prove_with_options(
+            prod_poly.clone(),
+            Fr::from(10),
+            bound_to(&commitment),
+        )
+        .unwrap();
+        let subclaim =
+            SumcheckVerifier::verify_partial_with_options(proof.clone(), &bound_to(&commitment))
+                .unwrap()
+                .remove(0);
+        assert_eq!(subclaim.challenges(), challenges[0].as_slice());
+        assert_eq!(
+            prod_poly.evaluate(subclaim.challenges()).unwrap(),
+            subclaim.sum()
+        );
+        // with the poly at hand the verifier can also run the final check itself
+        assert!(SumcheckVerifier::verify_with_options(
+            prod_poly.clone(),
+            proof.clone(),
+            &bound_to(&commitment)
+        )
+        .unwrap());
+
+        // the proof is bound to the commitment, under any other binding it is rejected
+        // either by a round check or by the final subclaim
+        let rejected = |result: Result<Vec<SubClaim<Fr>>, &'static str>| {
+            result.map_or(true, |subclaims| {
+                prod_poly.evaluate(subclaims[0].challenges()).unwrap() != subclaims[0].sum()
+            })
+        };
+        let other =
+            hash_commitment(&ProductPoly::new(vec![p_2ab_3bc(), p_2ab_3bc()]).unwrap()).unwrap();
+        assert_ne!(commitment, other);
+        assert!(rejected(SumcheckVerifier::verify_partial_with_options(
+            proof.clone(),
+            &bound_to(&other)
+        )));
+        assert!(SumcheckVerifier::verify(prod_poly.clone(), proof).is_err());
+
+        // proofs that absorbed the full poly don't verify in commitment mode
+        let full = SumcheckProver::prove(prod_poly.clone(), Fr::from(10)).unwrap();
+        assert!(rejected(SumcheckVerifier::verify_partial_with_options(
+            full,
+            &bound_to(&commitment)
+        )));
+
+        // commitment binding composes with repetitions, also through the scheme
+        let options = || SumcheckOptions {
+            config: SecurityConfig {
+                repetitions: 2,
+                ..Default::default()
+            },
+            ..bound_to(&commitment)
+        };
+        let (proof, challenges) =
+            Sumcheck::prove_with_options(prod_poly.clone(), Fr::from(10), options()).unwrap();
+        let subclaims = Sumcheck::verify_partial_with_options(proof, &options()).unwrap();
+        assert_eq!(subclaims.len(), 2);
+        for (subclaim, challenges) in subclaims.iter().zip(&challenges) {
+            assert_eq!(subclaim.challenges(), challenges.as_slice());
+            assert_eq!(prod_poly.evaluate(challenges).unwrap(), subclaim.sum());
+        }
+    }
+
+    #[test]
+    fn test_sumcheck_with_statement() {
+        let prod_poly = ProductPoly::new(vec![p_2ab_3bc()]).unwrap();
+        let statement = Statement::new(b"document hash".to_vec());
+        let other = Statement::from_field_elements(&[Fr::from(1), Fr::from(2)]);
+        let with_statement = |statement| SumcheckOptions {
+            statement: Some(statement),
+            ..SumcheckOptions::full(&SecurityConfig::default())
+        };
+
+        let (proof, _) = SumcheckProver::prove_with_options(
+            prod_poly.clone(),
+            Fr::from(10),
+            with_statement(&statement),
+        )
+        .unwrap();
+        assert!(SumcheckVerifier::verify_with_options(
+            prod_poly.clone(),
+            proof.clone(),
+            &with_statement(&statement)
+        )
+        .unwrap());
+        // bound to the statement, so neither another statement nor plain verify accept it
+        let rejected = |result: Result<bool, &'static str>| !matches!(result, Ok(true));
+        assert!(rejected(SumcheckVerifier::verify_with_options(
+            prod_poly.clone(),
+            proof.clone(),
+            &with_statement(&other)
+        )));
+        assert!(rejected(SumcheckVerifier::verify(prod_poly.clone(), proof)));
+
+        // a statement composes with the rest of the options, e.g. partial and repeated
+        let options = |statement| SumcheckOptions {
+            statement: Some(statement),
+            ..SumcheckOptions::partial(&SecurityConfig {
+                repetitions: 2,
+                ..Default::default()
+            })
+        };
+        let (proof, challenges) = SumcheckProver::prove_with_options(
+            prod_poly.clone(),
+            Fr::from(10),
+            options(&statement),
+        )
+        .unwrap();
+        let subclaims =
+            SumcheckVerifier::verify_partial_with_options(proof.clone(), &options(&statement))
+                .unwrap();
+        assert_eq!(subclaims.len(), 2);
+        for (subclaim, challenges) in subclaims.iter().zip(&challenges) {
+            assert_eq!(&subclaim.challenges, challenges);
+            assert_eq!(prod_poly.evaluate(challenges).unwrap(), subclaim.sum);
+        }
+        let other_subclaims =
+            SumcheckVerifier::verify_partial_with_options(proof, &options(&other));
+        assert!(other_subclaims.map_or(true, |subclaims| subclaims[0].challenges != challenges[0]));
+
+        assert_ne!(
+            statement.to_field_element::<Fr>(),
+            other.to_field_element::<Fr>()
+        );
+    }
+
+    #[test]
+    fn test_interactive_sumcheck() {
+        let prod_poly = ProductPoly::new(vec![p_2ab_3bc()]).unwrap();
+        let (mut prover_channel, verifier_channel) = message_channel(ark_std::test_rng());
+
+        let prover_poly = prod_poly.clone();
+        let prover = thread::spawn(move || {
+            SumcheckProver::prove_over_channel(
+                prover_poly,
+                Fr::from(10),
+                SumcheckOptions::default(),
+                &mut prover_channel,
+            )
+            .unwrap()
+        });
+        let mut replay = verifier_channel.serve::<Fr>().unwrap();
+        let (proof, challenges) = prover.join().unwrap();
+
+        let subclaim = SumcheckVerifier::verify_over_channel(
+            proof.clone(),
+            None,
+            &SumcheckOptions::default(),
+            &mut replay,
+        )
+        .unwrap()
+        .remove(0);
+        replay.finish().unwrap();
+        assert_eq!(subclaim.challenges, challenges[0]);
+        assert_eq!(prod_poly.evaluate(&challenges[0]).unwrap(), subclaim.sum);
+
+        // the proof doesn't verify non-interactively, challenges came from the verifier
+        assert!(SumcheckVerifier::verify(prod_poly, proof).is_err());
+    }
+
+    #[test]
+    fn test_prove_verify_through_scheme() {
+        fn prove_and_verify<S: SumcheckScheme<Fr, Polynomial = ProductPoly<Fr>>>(
+            poly: ProductPoly<Fr>,
+            sum: Fr,
+        ) -> bool {
+            let proof = S::prove(poly.clone(), sum).unwrap();
+            S::verify(poly, proof).unwrap_or(false)
+        }
+
+        let prod_poly = ProductPoly::new(vec![p_2ab_3bc()]).unwrap();
+        assert!(prove_and_verify::<Sumcheck<Fr>>(
+            prod_poly.clone(),
+            Fr::from(10)
+        ));
+        assert!(!prove_and_verify::<Sumcheck<Fr>>(prod_poly, Fr::from(12)));
+    }
+
+    #[test]
+    fn test_proof_is_reproducible() {
+        let prod_poly = ProductPoly::new(vec![p_2ab_3bc()]).unwrap();
+        let proof_a = SumcheckProver::prove(prod_poly.clone(), Fr::from(10)).unwrap();
+        let proof_b = SumcheckProver::prove(prod_poly.clone(), Fr::from(10)).unwrap();
+        assert_eq!(proof_a.to_bytes(), proof_b.to_bytes());
+
+        // round trip through bytes
+        let decoded = SumcheckProof::from_bytes(&proof_a.to_bytes()).unwrap();
+        assert_eq!(decoded, proof_a);
+        assert!(SumcheckVerifier::verify(prod_poly, decoded).unwrap());
+
+        assert!(SumcheckProof::<Fr>::from_bytes(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_proof_encodings_do_not_collide() {
+        let (a, b) = (Fr::from(1), Fr::from(2));
+        let proof = |round_polys: Vec<Vec<Fr>>| SumcheckProof {
+            sum: a,
+            max_variable_degree: 1,
+            round_polys,
+        };
+
+        // [[a, b], []] and [[a], [b]] must not collide
+        assert_ne!(
+            proof(vec![vec![a, b], vec![]]).to_bytes(),
+            proof(vec![vec![a], vec![b]]).to_bytes()
+        );
+        // a round poly must not be mistaken for an extra round
+        assert_ne!(
+            proof(vec![vec![a, b, a, b]]).to_bytes(),
+            proof(vec![vec![a, b], vec![a, b]]).to_bytes()
+        );
+        // same round polys, different degree
+        let mut other_degree = proof(vec![vec![a, b]]);
+        other_degree.max_variable_degree = 2;
+        assert_ne!(other_degree.to_bytes(), proof(vec![vec![a, b]]).to_bytes());
+
+        // only the byte order changes between endiannesses
+        let proof = proof(vec![vec![a, b]]);
+        let little = proof.to_bytes_with_endianness(Endianness::Little);
+        assert_eq!(
+            proof.to_bytes(),
+            proof.to_bytes_with_endianness(Endianness::Big)
+        );
+        assert_eq!(little.len(), proof.to_bytes().len());
+        assert_ne!(little, proof.to_bytes());
+        let mut streamed = vec![];
+        proof
+            .write_bytes_with_endianness(&mut streamed, Endianness::Little)
+            .unwrap();
+        assert_eq!(streamed, little);
+    }
+
+    #[test]
+    fn test_proof_bytes_round_trip_both_endiannesses() {
+        let prod_poly = ProductPoly::new(vec![p_2ab_3bc()]).unwrap();
+        let proof = SumcheckProver::prove(prod_poly, Fr::from(10)).unwrap();
+
+        for endianness in [Endianness::Big, Endianness::Little] {
+            let bytes = proof.to_bytes_with_endianness(endianness);
+            assert_eq!(
+                SumcheckProof::from_bytes_with_endianness(&bytes, endianness).unwrap(),
+                proof
+            );
+
+            // streaming leaves whatever follows the proof in the reader
+            let mut streamed = vec![];
+            proof
+                .write_bytes_with_endianness(&mut streamed, endianness)
+                .unwrap();
+            streamed.push(0xff);
+            let mut reader = streamed.as_slice();
+            assert_eq!(
+                SumcheckProof::<Fr>::read_bytes_with_endianness(&mut reader, endianness).unwrap(),
+                proof
+            );
+            assert_eq!(reader, [0xff]);
+        }
+
+        // the byte order has to match the one the proof was written with
+        let little = proof.to_bytes_with_endianness(Endianness::Little);
+        assert!(SumcheckProof::<Fr>::from_bytes(&little).is_err());
+        assert!(SumcheckProof::<Fr>::read_bytes(&mut proof.to_bytes().as_slice()).is_ok());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_proof_json_round_trip() {
+        let prod_poly = ProductPoly::new(vec![p_2ab_3bc()]).unwrap();
+        let proof = SumcheckProver::prove(prod_poly.clone(), Fr::from(10)).unwrap();
+
+        let json = serde_json::to_string(&proof).unwrap();
+        let decoded: SumcheckProof<Fr> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(SumcheckVerifier::verify(prod_poly, decoded).unwrap());
+    }
+
+    #[test]
+    fn test_proof_golden_bytes() {
+        // locks the proof encoding and the challenge derivation
+        // if this changes, proofs generated by earlier versions will no longer verify
+        let prod_poly = ProductPoly::new(vec![p_2ab_3bc()]).unwrap();
+        let proof = SumcheckProver::prove(prod_poly, Fr::from(10)).unwrap();
+        let hex = proof
+            .to_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        assert_eq!(hex, GOLDEN_PROOF_HEX);
+    }
+
+    const GOLDEN_PROOF_HEX: &str = concat!(
+        "000000000000000000000000000000000000000000000000000000000000000a",
+        "0000000000000001000000000000000300000000000000020000000000000000",
+        "0000000000000000000000000000000000000000000000030000000000000000",
+        "0000000000000000000000000000000000000000000000070000000000000002",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "057d1f7e11b8ba76d299cd2ece83d334bb6bdcb5ac710b6754a08a367c50ef8b",
+        "00000000000000024c140c93e7e685a8084fbe86ddc26437d306c1db71ff842d",
+        "f2ecbcc1c20c9bdc0848e99ab229794e3d991ccf1017d3194ee4185397009c7e",
+        "13fab5ddc9d41ab8",
+    );
 }
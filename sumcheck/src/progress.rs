@@ -0,0 +1,95 @@
+//! Progress reporting for long running proofs
+//! provers call back into a `ProgressObserver` as they go, every callback defaults to a no-op
+//! so observers only implement what they render (e.g. a progress bar only needs `on_percent`)
+
+/// Receives progress events from a prover
+pub trait ProgressObserver {
+    /// A layer starts, a plain sumcheck proof is a single layer
+    fn on_layer_start(&mut self, _layer: usize, _total_layers: usize) {}
+
+    /// A round finished (for every repetition), rounds are counted from 0
+    fn on_round_complete(&mut self, _round: usize, _total_rounds: usize) {}
+
+    /// Overall progress in whole percent, only reported when the value changes
+    fn on_percent(&mut self, _percent: u8) {}
+}
+
+/// Observer that ignores every event
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl ProgressObserver for NoProgress {}
+
+/// Turns round completions into percentages, reporting each value once
+#[derive(Debug, Default)]
+pub(crate) struct PercentTracker {
+    last: Option<u8>,
+}
+
+impl PercentTracker {
+    pub(crate) fn update(
+        &mut self,
+        completed: usize,
+        total: usize,
+        observer: &mut dyn ProgressObserver,
+    ) {
+        // nothing to do counts as done
+        let percent = (completed.min(total) * 100)
+            .checked_div(total)
+            .unwrap_or(100) as u8;
+        if self.last != Some(percent) {
+            self.last = Some(percent);
+            observer.on_percent(percent);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProgressObserver;
+    use crate::prover::SumcheckProver;
+    use crate::{SecurityConfig, SumcheckOptions};
+    use ark_bls12_381::Fr;
+    use polynomial::test_fields::random_product_poly;
+
+    #[derive(Default)]
+    struct Recorder {
+        layers: Vec<(usize, usize)>,
+        rounds: Vec<(usize, usize)>,
+        percents: Vec<u8>,
+    }
+
+    impl ProgressObserver for Recorder {
+        fn on_layer_start(&mut self, layer: usize, total_layers: usize) {
+            self.layers.push((layer, total_layers));
+        }
+
+        fn on_round_complete(&mut self, round: usize, total_rounds: usize) {
+            self.rounds.push((round, total_rounds));
+        }
+
+        fn on_percent(&mut self, percent: u8) {
+            self.percents.push(percent);
+        }
+    }
+
+    #[test]
+    fn test_prover_reports_progress() {
+        let mut rng = ark_std::test_rng();
+        let poly = random_product_poly::<Fr, _>(4, 1, &mut rng);
+        let sum = poly.sum_with_fixed_prefix(&[]).unwrap();
+
+        let mut recorder = Recorder::default();
+        let options = SumcheckOptions {
+            observer: Some(&mut recorder),
+            ..SumcheckOptions::full(&SecurityConfig::default())
+        };
+        let (proof, _) = SumcheckProver::prove_with_options(poly.clone(), sum, options).unwrap();
+        // observing doesn't change the proof
+        assert_eq!(proof, SumcheckProver::prove(poly, sum).unwrap());
+
+        assert_eq!(recorder.layers, vec![(0, 1)]);
+        assert_eq!(recorder.rounds, vec![(0, 4), (1, 4), (2, 4), (3, 4)]);
+        assert_eq!(recorder.percents, vec![0, 25, 50, 75, 100]);
+    }
+}
@@ -1,73 +1,151 @@
-use crate::{field_elements_to_bytes, SumcheckProof};
-use ark_ff::{BigInteger, PrimeField};
-use polynomial::product_poly::ProductPoly;
+use crate::progress::{NoProgress, PercentTracker, ProgressObserver};
+use crate::{SecurityConfig, SumcheckOptions, SumcheckProof};
+use ark_ff::PrimeField;
+use polynomial::encoding::{field_element_to_bytes, field_elements_to_bytes};
+use polynomial::SumcheckPolynomial;
 use std::marker::PhantomData;
+use transcript::channel::InteractiveChannel;
 use transcript::Transcript;
 
-/// `SumcheckProver`, initialized with the max_var_degree of the polynomial
+/// `SumcheckProver`
+/// the max variable degree of the polynomial is read from the polynomial at runtime
 /// this is used to determine how many points to evaluate the round polynomials
-pub struct SumcheckProver<const MAX_VAR_DEGREE: u8, F: PrimeField> {
+/// allocations are recorded under "sumcheck::prove", the caller prints them with
+/// `stat::memory::print_memory_report`
+pub struct SumcheckProver<F: PrimeField> {
     _marker: PhantomData<F>,
 }
 
-impl<const MAX_VAR_DEGREE: u8, F: PrimeField> SumcheckProver<MAX_VAR_DEGREE, F> {
+impl<F: PrimeField> SumcheckProver<F> {
     /// Generates the `Sumcheck` proof (appends the initial poly to the transcript)
-    pub fn prove(poly: ProductPoly<F>, sum: F) -> Result<SumcheckProof<F>, &'static str> {
-        let mut transcript = Transcript::new();
-        transcript.append(poly.to_bytes().as_slice());
+    pub fn prove<P: SumcheckPolynomial<F>>(
+        poly: P,
+        sum: F,
+    ) -> Result<SumcheckProof<F>, &'static str> {
+        Self::prove_with_config(poly, sum, &SecurityConfig::default())
+    }
+
+    /// Generates the `Sumcheck` proof with the given `SecurityConfig`
+    pub fn prove_with_config<P: SumcheckPolynomial<F>>(
+        poly: P,
+        sum: F,
+        config: &SecurityConfig,
+    ) -> Result<SumcheckProof<F>, &'static str> {
+        Ok(Self::prove_with_options(poly, sum, SumcheckOptions::full(config))?.0)
+    }
 
-        Ok(Self::prove_internal(poly, sum, &mut transcript)?.0)
+    /// Generates the `Sumcheck` proof as described by the options (config, statement and
+    /// what the transcript is bound to), returns the challenges for each repetition
+    /// verify with `SumcheckVerifier::verify_with_options` or `verify_partial_with_options`
+    pub fn prove_with_options<P: SumcheckPolynomial<F>>(
+        poly: P,
+        sum: F,
+        options: SumcheckOptions,
+    ) -> Result<(SumcheckProof<F>, Vec<Vec<F>>), &'static str> {
+        Self::prove_over_channel(poly, sum, options, &mut Transcript::new())
     }
 
     /// Generates the `Sumcheck` proof, but doesn't append the initial poly to the transcript.
     /// This is used when the verifier doesn't have access to the initial poly or its commitment
-    pub fn prove_partial(
-        poly: ProductPoly<F>,
+    /// (e.g. an `OraclePoly`, checked by querying the oracle at the challenges)
+    pub fn prove_partial<P: SumcheckPolynomial<F>>(
+        poly: P,
         sum: F,
     ) -> Result<(SumcheckProof<F>, Vec<F>), &'static str> {
-        let mut transcript = Transcript::new();
-        Self::prove_internal(poly, sum, &mut transcript)
+        let (proof, mut challenges) =
+            Self::prove_partial_with_config(poly, sum, &SecurityConfig::default())?;
+        Ok((proof, challenges.remove(0)))
+    }
+
+    /// Generates the partial `Sumcheck` proof with the given `SecurityConfig`
+    /// returns the challenges for each repetition
+    pub fn prove_partial_with_config<P: SumcheckPolynomial<F>>(
+        poly: P,
+        sum: F,
+        config: &SecurityConfig,
+    ) -> Result<(SumcheckProof<F>, Vec<Vec<F>>), &'static str> {
+        Self::prove_with_options(poly, sum, SumcheckOptions::partial(config))
+    }
+
+    /// Runs the `Sumcheck` prover over the given channel
+    /// use a `ProverChannel` to run the protocol interactively, challenges then come from the verifier
+    pub fn prove_over_channel<P: SumcheckPolynomial<F>, C: InteractiveChannel>(
+        poly: P,
+        sum: F,
+        options: SumcheckOptions,
+        channel: &mut C,
+    ) -> Result<(SumcheckProof<F>, Vec<Vec<F>>), &'static str> {
+        options.append_to_transcript(Some(&poly), channel)?;
+        let observer = match options.observer {
+            Some(observer) => observer,
+            None => &mut NoProgress,
+        };
+        Self::prove_internal(poly, sum, &options.config, channel, observer)
     }
 
     /// Main `Sumcheck` proof generation logic.
-    fn prove_internal(
-        mut poly: ProductPoly<F>,
+    /// runs all repetitions in lockstep, for each round the round polys of every
+    /// repetition are added to the transcript before any of the round challenges are sampled
+    fn prove_internal<P: SumcheckPolynomial<F>, C: InteractiveChannel>(
+        poly: P,
         sum: F,
-        transcript: &mut Transcript,
-    ) -> Result<(SumcheckProof<F>, Vec<F>), &'static str> {
+        config: &SecurityConfig,
+        transcript: &mut C,
+        observer: &mut dyn ProgressObserver,
+    ) -> Result<(SumcheckProof<F>, Vec<Vec<F>>), &'static str> {
+        let _span = stat::trace_span!("sumcheck::prove", n_vars = poly.n_vars());
+        let _mem = stat::record_alloc_scope!("sumcheck::prove");
+
+        config.validate()?;
+        config.append_to_transcript(transcript)?;
+
         let mut round_polys = vec![];
-        let mut challenges = vec![];
+        let mut challenges = vec![vec![]; config.repetitions];
+        let n_vars = poly.n_vars();
+        let max_variable_degree = poly.max_variable_degree();
+        let mut polys = vec![poly; config.repetitions];
+
+        let mut progress = PercentTracker::default();
+        observer.on_layer_start(0, 1);
+        progress.update(0, n_vars, observer);
 
         // append the sum to the transcript
-        transcript.append(sum.into_bigint().to_bytes_be().as_slice());
-
-        for _ in 0..poly.n_vars() {
-            // calculate round_poly
-            // for a round poly of a certain degree d (denoted by MAX_VAR_DEGREE)
-            // we evaluate the polynomial at d + 1 points
-            let mut round_poly = vec![];
-            for i in 0..=MAX_VAR_DEGREE {
-                round_poly.push(
-                    poly.partial_evaluate(0, &[F::from(i)])?
-                        .prod_reduce()
-                        .iter()
-                        .sum::<F>(),
-                )
-            }
+        transcript.append(field_element_to_bytes(&sum).as_slice())?;
+
+        for round in 0..n_vars {
+            let _round_span = stat::trace_span!("sumcheck::round", round = round);
 
-            // add round_poly to transcript
-            transcript.append(field_elements_to_bytes(&round_poly).as_slice());
+            for poly in &polys {
+                // calculate round_poly
+                // for a round poly of a certain degree d (the max variable degree)
+                // we evaluate the polynomial at d + 1 points
+                let round_poly = poly.round_evaluations()?;
 
-            // generate challenge
-            let challenge = transcript.sample_field_element::<F>();
-            // partially evaluate the poly at the challenge
-            poly = poly.partial_evaluate(0, &[challenge])?;
+                // add round_poly to transcript
+                transcript.append(field_elements_to_bytes(&round_poly).as_slice())?;
+                round_polys.push(round_poly);
+            }
+
+            for (repetition, (poly, repetition_challenges)) in
+                polys.iter_mut().zip(challenges.iter_mut()).enumerate()
+            {
+                // generate challenge
+                config.append_round_tag(round, repetition, transcript)?;
+                let challenge = transcript.challenge::<F>()?;
+                // fix the first variable of every factor to the challenge
+                poly.fold_in_place(&challenge)?;
+                repetition_challenges.push(challenge);
+            }
 
-            round_polys.push(round_poly);
-            challenges.push(challenge);
+            observer.on_round_complete(round, n_vars);
+            progress.update(round + 1, n_vars, observer);
         }
 
-        let proof = SumcheckProof { sum, round_polys };
+        let proof = SumcheckProof {
+            sum,
+            max_variable_degree,
+            round_polys,
+        };
 
         Ok((proof, challenges))
     }
@@ -0,0 +1,343 @@
+//! Sumcheck consistency vectors
+//! a fixture records a product poly (as the evaluation tables of its factors), the claimed
+//! sum and the round polys and challenges of a partial proof (the poly is not absorbed).
+//! Derivation, with field elements and lengths in the big endian `polynomial::encoding`:
+//! absorb "sumcheck/round-tags" and the sum, then for every round absorb the round poly
+//! (evaluations at 0, 1, ..., d) and "sumcheck/round/<round>", and sample the challenge as the
+//! Keccak256 digest reduced mod p, d being the factor count.
+//! In JSON every field element is its canonical decimal string, tables are nested arrays.
+//! These only check internal consistency: the checked in fixtures (tests/fixtures/sumcheck.json,
+//! printed by `cargo run -p sumcheck --features test-vectors --bin test-vectors`) come from our
+//! own prover and are cross checked against a reference prover in the tests of this module,
+//! no fixture from an external implementation is checked in yet.
+//! Fixtures from another implementation following the same derivation can be checked with
+//! `SumcheckFixture::check`.
+
+use crate::prover::SumcheckProver;
+use crate::verifier::SumcheckVerifier;
+use crate::SumcheckProof;
+use ark_ff::PrimeField;
+use polynomial::multilinear::evaluation_form::MultiLinearPolynomial;
+use polynomial::product_poly::ProductPoly;
+
+/// Product poly, claimed sum and the partial proof transcript for it
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        bound = "F: PrimeField",
+        into = "FixtureJson",
+        try_from = "FixtureJson"
+    )
+)]
+pub struct SumcheckFixture<F: PrimeField> {
+    pub name: String,
+    pub n_vars: usize,
+    /// evaluation table of every factor, variable 0 is the most significant bit of the index
+    pub factors: Vec<Vec<F>>,
+    pub sum: F,
+    pub round_polys: Vec<Vec<F>>,
+    pub challenges: Vec<F>,
+}
+
+/// JSON form of a fixture, field elements as canonical decimal strings
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct FixtureJson {
+    name: String,
+    n_vars: usize,
+    factors: Vec<Vec<String>>,
+    sum: String,
+    round_polys: Vec<Vec<String>>,
+    challenges: Vec<String>,
+}
+
+#[cfg(feature = "serde")]
+impl<F: PrimeField> From<SumcheckFixture<F>> for FixtureJson {
+    fn from(fixture: SumcheckFixture<F>) -> Self {
+        let encode = |elements: &[F]| elements.iter().map(to_decimal).collect::<Vec<_>>();
+        Self {
+            name: fixture.name,
+            n_vars: fixture.n_vars,
+            factors: fixture.factors.iter().map(|table| encode(table)).collect(),
+            sum: to_decimal(&fixture.sum),
+            round_polys: fixture
+                .round_polys
+                .iter()
+                .map(|poly| encode(poly))
+                .collect(),
+            challenges: encode(&fixture.challenges),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<F: PrimeField> TryFrom<FixtureJson> for SumcheckFixture<F> {
+    type Error = &'static str;
+
+    fn try_from(json: FixtureJson) -> Result<Self, Self::Error> {
+        let decode = |elements: &[String]| {
+            elements
+                .iter()
+                .map(|element| from_decimal(element))
+                .collect::<Result<Vec<F>, _>>()
+        };
+        Ok(Self {
+            name: json.name,
+            n_vars: json.n_vars,
+            factors: json
+                .factors
+                .iter()
+                .map(|table| decode(table))
+                .collect::<Result<_, _>>()?,
+            sum: from_decimal(&json.sum)?,
+            round_polys: json
+                .round_polys
+                .iter()
+                .map(|poly| decode(poly))
+                .collect::<Result<_, _>>()?,
+            challenges: decode(&json.challenges)?,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+fn to_decimal<F: PrimeField>(element: &F) -> String {
+    element.into_bigint().to_string()
+}
+
+/// Parses a canonical decimal field element
+/// rejects values not below the modulus, signs and leading zeros
+#[cfg(feature = "serde")]
+fn from_decimal<F: PrimeField>(decimal: &str) -> Result<F, &'static str> {
+    let element = F::from_str(decimal).map_err(|_| "invalid decimal field element")?;
+    if to_decimal(&element) != decimal {
+        return Err("field element isn't in canonical decimal form");
+    }
+    Ok(element)
+}
+
+impl<F: PrimeField> SumcheckFixture<F> {
+    /// Runs our prover over the product of the factors
+    pub fn generate(name: &str, n_vars: usize, factors: Vec<Vec<F>>) -> Result<Self, &'static str> {
+        let poly = product_poly(n_vars, &factors)?;
+        let sum = poly.sum_with_fixed_prefix(&[])?;
+        let (proof, challenges) = SumcheckProver::prove_partial(poly, sum)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            n_vars,
+            factors,
+            sum,
+            round_polys: proof.round_polys,
+            challenges,
+        })
+    }
+
+    /// Checks the fixture against our verifier
+    /// the round polys must verify, derive the recorded challenges and
+    /// end in a subclaim the product poly satisfies
+    pub fn check(&self) -> Result<(), &'static str> {
+        let poly = product_poly(self.n_vars, &self.factors)?;
+        let proof = SumcheckProof {
+            sum: self.sum,
+            max_variable_degree: poly.max_variable_degree(),
+            round_polys: self.round_polys.clone(),
+        };
+
+        let subclaim = SumcheckVerifier::verify_partial_bounded(
+            proof,
+            self.n_vars,
+            poly.max_variable_degree(),
+        )?;
+        if subclaim.challenges != self.challenges {
+            return Err("fixture challenges don't match the derived challenges");
+        }
+        if poly.evaluate(&subclaim.challenges)? != subclaim.sum {
+            return Err("fixture final claim doesn't match the polynomial");
+        }
+        Ok(())
+    }
+}
+
+/// Fixtures for a few small polys, the factors are derived from their index
+/// so they are the same across runs and platforms
+pub fn default_fixtures<F: PrimeField>() -> Result<Vec<SumcheckFixture<F>>, &'static str> {
+    let table = |n_vars: usize, seed: u64| {
+        (0..1_u64 << n_vars)
+            .map(|i| F::from((i + 1) * (seed + 3) % 101))
+            .collect::<Vec<_>>()
+    };
+
+    vec![
+        SumcheckFixture::generate("single_variable", 1, vec![table(1, 0)]),
+        SumcheckFixture::generate("multilinear", 3, vec![table(3, 0)]),
+        SumcheckFixture::generate("degree_2", 3, vec![table(3, 0), table(3, 1)]),
+        SumcheckFixture::generate("degree_3", 4, vec![table(4, 0), table(4, 1), table(4, 2)]),
+    ]
+    .into_iter()
+    .collect()
+}
+
+fn product_poly<F: PrimeField>(
+    n_vars: usize,
+    factors: &[Vec<F>],
+) -> Result<ProductPoly<F>, &'static str> {
+    ProductPoly::new(
+        factors
+            .iter()
+            .map(|evaluations| MultiLinearPolynomial::new(n_vars, evaluations.clone()))
+            .collect::<Result<Vec<_>, _>>()?,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_fixtures, SumcheckFixture};
+    use ark_bls12_381::Fr;
+    use ark_ff::{Field, PrimeField};
+    use polynomial::encoding::{field_element_to_bytes, field_elements_to_bytes};
+    use transcript::Transcript;
+
+    /// Independent sumcheck prover written from the derivation in the module doc
+    /// evaluates the multilinear extensions straight from their definition,
+    /// f(r) = sum_b f(b) prod_i (r_i b_i + (1 - r_i)(1 - b_i)), nothing is folded
+    fn reference_fixture<F: PrimeField>(
+        n_vars: usize,
+        factors: &[Vec<F>],
+    ) -> (F, Vec<Vec<F>>, Vec<F>) {
+        let extension = |table: &[F], point: &[F]| {
+            table
+                .iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    point.iter().enumerate().fold(*value, |acc, (i, r)| {
+                        let bit = (index >> (n_vars - 1 - i)) & 1 == 1;
+                        acc * if bit { *r } else { F::ONE - r }
+                    })
+                })
+                .sum::<F>()
+        };
+        let product = |point: &[F]| {
+            factors
+                .iter()
+                .map(|table| extension(table, point))
+                .product::<F>()
+        };
+        let bits = |index: usize, len: usize| {
+            (0..len)
+                .map(|i| F::from(((index >> (len - 1 - i)) & 1) as u64))
+                .collect::<Vec<_>>()
+        };
+
+        let sum = (0..1 << n_vars)
+            .map(|index| product(&bits(index, n_vars)))
+            .sum::<F>();
+        let mut transcript = Transcript::new();
+        transcript.append(b"sumcheck/round-tags");
+        transcript.append(&field_element_to_bytes(&sum));
+
+        let (mut round_polys, mut challenges) = (vec![], vec![]);
+        for round in 0..n_vars {
+            let remaining = n_vars - round - 1;
+            let round_poly = (0..=factors.len())
+                .map(|t| {
+                    (0..1 << remaining)
+                        .map(|index| {
+                            let point = [
+                                challenges.as_slice(),
+                                &[F::from(t as u64)],
+                                &bits(index, remaining),
+                            ]
+                            .concat();
+                            product(&point)
+                        })
+                        .sum::<F>()
+                })
+                .collect::<Vec<_>>();
+            transcript.append(&field_elements_to_bytes(&round_poly));
+            transcript.append(format!("sumcheck/round/{round}").as_bytes());
+            challenges.push(transcript.sample_field_element::<F>());
+            round_polys.push(round_poly);
+        }
+
+        (sum, round_polys, challenges)
+    }
+
+    #[test]
+    fn test_fixtures_match_reference() {
+        for fixture in default_fixtures::<Fr>().unwrap() {
+            fixture.check().unwrap();
+
+            let (sum, round_polys, challenges) =
+                reference_fixture(fixture.n_vars, &fixture.factors);
+            let reference = SumcheckFixture {
+                sum,
+                round_polys,
+                challenges,
+                ..fixture.clone()
+            };
+            assert_eq!(reference, fixture, "{}", fixture.name);
+        }
+    }
+
+    #[test]
+    fn test_tampered_fixture_rejected() {
+        let fixture = default_fixtures::<Fr>().unwrap().remove(2);
+
+        let mut tampered = fixture.clone();
+        tampered.challenges[1] += Fr::ONE;
+        assert!(tampered.check().is_err());
+
+        let mut tampered = fixture.clone();
+        tampered.factors[0][0] += Fr::ONE;
+        assert!(tampered.check().is_err());
+
+        let mut tampered = fixture;
+        tampered.round_polys[0][0] += Fr::ONE;
+        assert!(tampered.check().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_fixture_json_round_trip() {
+        let fixtures = default_fixtures::<Fr>().unwrap();
+        let json = serde_json::to_string(&fixtures).unwrap();
+        let decoded: Vec<SumcheckFixture<Fr>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, fixtures);
+        decoded.iter().for_each(|fixture| fixture.check().unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_fixture_json_decimal_elements() {
+        let fixture = default_fixtures::<Fr>().unwrap().remove(0);
+        let json = serde_json::to_value(&fixture).unwrap();
+        assert_eq!(json["sum"], "9");
+        assert_eq!(json["factors"], serde_json::json!([["3", "6"]]));
+
+        let decode = |sum: &str| {
+            let mut json = json.clone();
+            json["sum"] = sum.into();
+            serde_json::from_value::<SumcheckFixture<Fr>>(json)
+        };
+        assert_eq!(decode("9").unwrap(), fixture);
+        assert!(decode("09").is_err());
+        assert!(decode("+9").is_err());
+        assert!(decode("0x09").is_err());
+        // the modulus reduces to 0 but isn't canonical
+        let modulus = Fr::MODULUS.to_string();
+        assert!(decode(&modulus).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_checked_in_fixtures_consistent() {
+        // our own output, regenerate with the test-vectors binary if the derivation changes on purpose
+        let json = include_str!("../tests/fixtures/sumcheck.json");
+        let fixtures: Vec<SumcheckFixture<Fr>> = serde_json::from_str(json).unwrap();
+        fixtures.iter().for_each(|fixture| fixture.check().unwrap());
+        assert_eq!(fixtures, default_fixtures::<Fr>().unwrap());
+    }
+}
@@ -1,8 +1,10 @@
-use crate::{field_elements_to_bytes, SubClaim, SumcheckProof};
-use ark_ff::{BigInteger, PrimeField};
+use crate::{SecurityConfig, SubClaim, SumcheckOptions, SumcheckProof};
+use ark_ff::PrimeField;
+use polynomial::encoding::{field_element_to_bytes, field_elements_to_bytes};
 use polynomial::product_poly::ProductPoly;
 use polynomial::univariate_poly::UnivariatePolynomial;
 use std::marker::PhantomData;
+use transcript::channel::InteractiveChannel;
 use transcript::Transcript;
 
 /// Sumcheck Verifier
@@ -13,67 +15,184 @@ pub struct SumcheckVerifier<F: PrimeField> {
 impl<F: PrimeField> SumcheckVerifier<F> {
     /// Verify a `Sumcheck` proof (verifier has access to the initial poly or its commitment)
     pub fn verify(poly: ProductPoly<F>, proof: SumcheckProof<F>) -> Result<bool, &'static str> {
-        // number of round_poly in the proof should match n_vars
-        if proof.round_polys.len() != poly.n_vars() {
-            return Err("invalid proof: require 1 round poly for each variable in poly");
-        }
+        Self::verify_with_config(poly, proof, &SecurityConfig::default())
+    }
 
-        let mut transcript = Transcript::new();
-        transcript.append(poly.to_bytes().as_slice());
+    /// Verify a `Sumcheck` proof generated with the given `SecurityConfig`
+    /// every repetition must pass for the proof to be accepted
+    pub fn verify_with_config(
+        poly: ProductPoly<F>,
+        proof: SumcheckProof<F>,
+        config: &SecurityConfig,
+    ) -> Result<bool, &'static str> {
+        Self::verify_with_options(poly, proof, &SumcheckOptions::full(config))
+    }
+
+    /// Verify a proof from `SumcheckProver::prove_with_options`, including the final check
+    /// against the poly, the options must be the ones the proof was generated with
+    pub fn verify_with_options(
+        poly: ProductPoly<F>,
+        proof: SumcheckProof<F>,
+        options: &SumcheckOptions,
+    ) -> Result<bool, &'static str> {
+        // the proof must be for a poly of the same degree
+        if proof.max_variable_degree != poly.max_variable_degree() {
+            return Err("invalid proof: proof degree doesn't match the poly degree");
+        }
 
-        let subclaim = Self::verify_internal(proof, &mut transcript)?;
+        let subclaims = Self::verify_internal(
+            proof,
+            Some(poly.n_vars()),
+            Some(poly.max_variable_degree()),
+            Some(&poly),
+            options,
+            &mut Transcript::new(),
+        )?;
 
         // final verifier check
         // p_v(r_v) = p(r_1, r_2, ..., r_v)
-        let initial_poly_eval = poly
-            .evaluate(subclaim.challenges.as_slice())
-            .map_err(|_| "couldn't evaluate initial poly")?;
-        // ensure the oracle evaluation equals the claimed sum
-        Ok(initial_poly_eval == subclaim.sum)
+        for subclaim in subclaims {
+            let initial_poly_eval = poly
+                .evaluate(subclaim.challenges.as_slice())
+                .map_err(|_| "couldn't evaluate initial poly")?;
+            // ensure the oracle evaluation equals the claimed sum
+            if initial_poly_eval != subclaim.sum {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Verify a partial proof from `SumcheckProver::prove_with_options`
+    /// returns one subclaim per repetition, all of them must hold
+    pub fn verify_partial_with_options(
+        proof: SumcheckProof<F>,
+        options: &SumcheckOptions,
+    ) -> Result<Vec<SubClaim<F>>, &'static str> {
+        Self::verify_internal(proof, None, None, None, options, &mut Transcript::new())
     }
 
     /// Verify a `Sumcheck` proof (when the veifier doesn't have access to the initial poly or its commitment)
     /// in such a case, the verifier performs all checks other than the last check.
     /// Returns a subclaim that can later be used for that final check verification.
     pub fn verify_partial(proof: SumcheckProof<F>) -> Result<SubClaim<F>, &'static str> {
-        let mut transcript = Transcript::new();
-        Self::verify_internal(proof, &mut transcript)
+        Ok(Self::verify_partial_with_config(proof, &SecurityConfig::default())?.remove(0))
     }
 
-    /// Main `Sumcheck` verification logic.
-    fn verify_internal(
+    /// Verify a partial `Sumcheck` proof generated with the given `SecurityConfig`
+    /// returns one subclaim per repetition, all of them must hold
+    pub fn verify_partial_with_config(
         proof: SumcheckProof<F>,
-        transcript: &mut Transcript,
+        config: &SecurityConfig,
+    ) -> Result<Vec<SubClaim<F>>, &'static str> {
+        Self::verify_partial_with_options(proof, &SumcheckOptions::partial(config))
+    }
+
+    /// Verify a partial `Sumcheck` proof when the verifier knows the shape of the poly
+    /// (but not the poly), the proof is rejected before any transcript work if it doesn't
+    /// have n_vars rounds or its degree exceeds degree_bound
+    pub fn verify_partial_bounded(
+        proof: SumcheckProof<F>,
+        n_vars: usize,
+        degree_bound: usize,
     ) -> Result<SubClaim<F>, &'static str> {
-        let mut challenges = vec![];
+        let options = SumcheckOptions::partial(&SecurityConfig::default());
+        Ok(Self::verify_internal(
+            proof,
+            Some(n_vars),
+            Some(degree_bound),
+            None,
+            &options,
+            &mut Transcript::new(),
+        )?
+        .remove(0))
+    }
+
+    /// Runs the `Sumcheck` verifier over the given channel, up to the final check
+    /// the poly is only needed (and absorbed) when the options bind the proof to it,
+    /// when given it also bounds the proof degree
+    /// use the `ReplayChannel` recorded by a `VerifierChannel` to check an interactive run,
+    /// the proof must match the messages sent during the interaction, then call
+    /// `ReplayChannel::finish` so a proof covering only part of the run is rejected
+    pub fn verify_over_channel<C: InteractiveChannel>(
+        proof: SumcheckProof<F>,
+        poly: Option<&ProductPoly<F>>,
+        options: &SumcheckOptions,
+        channel: &mut C,
+    ) -> Result<Vec<SubClaim<F>>, &'static str> {
+        Self::verify_internal(
+            proof,
+            poly.map(|poly| poly.n_vars()),
+            poly.map(|poly| poly.max_variable_degree()),
+            poly,
+            options,
+            channel,
+        )
+    }
 
-        transcript.append(proof.sum.into_bigint().to_bytes_be().as_slice());
+    /// Main `Sumcheck` verification logic.
+    /// the proof structure is checked against n_vars and degree_bound (when known) first,
+    /// the poly is only used when the options bind the proof to it
+    fn verify_internal<C: InteractiveChannel>(
+        proof: SumcheckProof<F>,
+        n_vars: Option<usize>,
+        degree_bound: Option<usize>,
+        poly: Option<&ProductPoly<F>>,
+        options: &SumcheckOptions,
+        transcript: &mut C,
+    ) -> Result<Vec<SubClaim<F>>, &'static str> {
+        let config = &options.config;
+        proof.check_structure(n_vars, degree_bound, config)?;
+
+        options.append_to_transcript(poly, transcript)?;
+        config.append_to_transcript(transcript)?;
+
+        let mut challenges = vec![vec![]; config.repetitions];
 
-        let mut claimed_sum = proof.sum;
+        transcript.append(field_element_to_bytes(&proof.sum).as_slice())?;
 
-        for round_poly in proof.round_polys {
-            // append the round poly to the transcript
-            transcript.append(field_elements_to_bytes(&round_poly).as_slice());
+        let mut claimed_sums = vec![proof.sum; config.repetitions];
 
-            let round_univariate_poly = UnivariatePolynomial::interpolate(round_poly);
+        // round polys are stored round by round, each round holds one poly per repetition
+        for (round_index, round) in proof.round_polys.chunks(config.repetitions).enumerate() {
+            let mut round_univariate_polys = vec![];
+            for (round_poly, claimed_sum) in round.iter().zip(claimed_sums.iter()) {
+                // append the round poly to the transcript
+                transcript.append(field_elements_to_bytes(round_poly).as_slice())?;
 
-            // assert that p(0) + p(1) = sum
-            let p_0 = round_univariate_poly.evaluate(&F::ZERO);
-            let p_1 = round_univariate_poly.evaluate(&F::ONE);
+                let round_univariate_poly = UnivariatePolynomial::interpolate(round_poly.clone())?;
 
-            if claimed_sum != (p_0 + p_1) {
-                return Err("verifier check failed: claimed_sum != p(0) + p(1)");
+                // assert that p(0) + p(1) = sum
+                let p_0 = round_univariate_poly.evaluate(&F::ZERO);
+                let p_1 = round_univariate_poly.evaluate(&F::ONE);
+
+                if *claimed_sum != (p_0 + p_1) {
+                    return Err("verifier check failed: claimed_sum != p(0) + p(1)");
+                }
+
+                round_univariate_polys.push(round_univariate_poly);
             }
 
             // sample challenge and update claimed sum
-            let challenge = transcript.sample_field_element::<F>();
-            claimed_sum = round_univariate_poly.evaluate(&challenge);
-            challenges.push(challenge);
+            for (repetition, ((round_univariate_poly, claimed_sum), repetition_challenges)) in
+                round_univariate_polys
+                    .iter()
+                    .zip(claimed_sums.iter_mut())
+                    .zip(challenges.iter_mut())
+                    .enumerate()
+            {
+                config.append_round_tag(round_index, repetition, transcript)?;
+                let challenge = transcript.challenge::<F>()?;
+                *claimed_sum = round_univariate_poly.evaluate(&challenge);
+                repetition_challenges.push(challenge);
+            }
         }
 
-        Ok(SubClaim {
-            sum: claimed_sum,
-            challenges,
-        })
+        Ok(claimed_sums
+            .into_iter()
+            .zip(challenges)
+            .map(|(sum, challenges)| SubClaim { sum, challenges })
+            .collect())
     }
 }
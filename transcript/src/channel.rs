@@ -0,0 +1,250 @@
+//! Channels between a prover and a verifier
+//! the protocols only need two operations from the channel:
+//! - append: record a message sent by the prover
+//! - challenge: obtain the verifier's next random challenge
+//!
+//! `Transcript` implements the channel non-interactively (Fiat-Shamir).
+//! `message_channel` builds a truly interactive channel, prover messages are sent to the verifier
+//! and challenges are freshly sampled by the verifier then sent back to the prover.
+
+use crate::Transcript;
+use ark_ff::PrimeField;
+use ark_std::rand::RngCore;
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/// Public coin channel between a prover and a verifier
+pub trait InteractiveChannel {
+    /// Record a prover message
+    fn append(&mut self, message: &[u8]) -> Result<(), &'static str>;
+
+    /// Get the next verifier challenge
+    fn challenge<F: PrimeField>(&mut self) -> Result<F, &'static str>;
+}
+
+impl InteractiveChannel for Transcript {
+    fn append(&mut self, message: &[u8]) -> Result<(), &'static str> {
+        Transcript::append(self, message);
+        Ok(())
+    }
+
+    fn challenge<F: PrimeField>(&mut self) -> Result<F, &'static str> {
+        Ok(self.sample_field_element())
+    }
+}
+
+/// Messages sent from the prover to the verifier
+enum ProverMessage {
+    Message(Vec<u8>),
+    ChallengeRequest,
+}
+
+/// Builds a connected (prover, verifier) channel pair
+/// the verifier samples its challenges from the given rng
+pub fn message_channel<R: RngCore>(rng: R) -> (ProverChannel, VerifierChannel<R>) {
+    let (to_verifier, from_prover) = channel();
+    let (to_prover, from_verifier) = channel();
+    (
+        ProverChannel {
+            to_verifier,
+            from_verifier,
+        },
+        VerifierChannel {
+            from_prover,
+            to_prover,
+            rng,
+            events: vec![],
+        },
+    )
+}
+
+/// Prover end of an interactive channel
+pub struct ProverChannel {
+    to_verifier: Sender<ProverMessage>,
+    from_verifier: Receiver<Vec<u8>>,
+}
+
+impl InteractiveChannel for ProverChannel {
+    fn append(&mut self, message: &[u8]) -> Result<(), &'static str> {
+        self.to_verifier
+            .send(ProverMessage::Message(message.to_vec()))
+            .map_err(|_| "verifier disconnected")
+    }
+
+    fn challenge<F: PrimeField>(&mut self) -> Result<F, &'static str> {
+        self.to_verifier
+            .send(ProverMessage::ChallengeRequest)
+            .map_err(|_| "verifier disconnected")?;
+        let bytes = self
+            .from_verifier
+            .recv()
+            .map_err(|_| "verifier disconnected")?;
+        F::deserialize_compressed(bytes.as_slice()).map_err(|_| "invalid challenge encoding")
+    }
+}
+
+/// Verifier end of an interactive channel
+/// records every prover message and every challenge it sends, in the order they happened
+pub struct VerifierChannel<R: RngCore> {
+    from_prover: Receiver<ProverMessage>,
+    to_prover: Sender<Vec<u8>>,
+    rng: R,
+    events: Vec<Event>,
+}
+
+/// A step of a recorded interaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Event {
+    Message(Vec<u8>),
+    Challenge(Vec<u8>),
+}
+
+impl<R: RngCore> VerifierChannel<R> {
+    /// Answer prover challenge requests until the prover disconnects
+    /// returns the record of the interaction, which the verifier then checks the proof against
+    pub fn serve<F: PrimeField>(mut self) -> Result<ReplayChannel, &'static str> {
+        while let Ok(message) = self.from_prover.recv() {
+            match message {
+                ProverMessage::Message(bytes) => self.events.push(Event::Message(bytes)),
+                ProverMessage::ChallengeRequest => {
+                    let mut challenge = vec![];
+                    F::rand(&mut self.rng)
+                        .serialize_compressed(&mut challenge)
+                        .map_err(|_| "failed to serialize challenge")?;
+                    self.to_prover
+                        .send(challenge.clone())
+                        .map_err(|_| "prover disconnected")?;
+                    self.events.push(Event::Challenge(challenge));
+                }
+            }
+        }
+
+        Ok(ReplayChannel {
+            events: self.events.into(),
+        })
+    }
+}
+
+/// Replays a recorded interaction
+/// appended messages and requested challenges must follow the recorded interaction in order,
+/// challenges are the ones the verifier sent during the interaction,
+/// call `finish` once the verifier is done to check nothing was left unreplayed
+pub struct ReplayChannel {
+    events: VecDeque<Event>,
+}
+
+impl ReplayChannel {
+    /// Ends the replay, errors if any recorded message or challenge wasn't consumed
+    /// (the verifier then only checked part of the interaction)
+    pub fn finish(self) -> Result<(), &'static str> {
+        if !self.events.is_empty() {
+            return Err("recorded events left in the interaction");
+        }
+        Ok(())
+    }
+}
+
+impl InteractiveChannel for ReplayChannel {
+    fn append(&mut self, message: &[u8]) -> Result<(), &'static str> {
+        match self.events.pop_front() {
+            Some(Event::Message(recorded)) if recorded == message => Ok(()),
+            _ => Err("message doesn't match the recorded interaction"),
+        }
+    }
+
+    fn challenge<F: PrimeField>(&mut self) -> Result<F, &'static str> {
+        match self.events.pop_front() {
+            Some(Event::Challenge(bytes)) => F::deserialize_compressed(bytes.as_slice())
+                .map_err(|_| "invalid challenge encoding"),
+            _ => Err("challenge doesn't match the recorded interaction"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{message_channel, Event, InteractiveChannel, ReplayChannel};
+    use crate::Transcript;
+    use ark_bls12_381::Fr;
+    use std::thread;
+
+    #[test]
+    fn test_message_channel_replay() {
+        let (mut prover, verifier) = message_channel(ark_std::test_rng());
+
+        let prover = thread::spawn(move || {
+            prover.append(b"first").unwrap();
+            let a: Fr = prover.challenge().unwrap();
+            prover.append(b"second").unwrap();
+            let b: Fr = prover.challenge().unwrap();
+            (a, b)
+        });
+        let mut replay = verifier.serve::<Fr>().unwrap();
+        let (a, b) = prover.join().unwrap();
+
+        replay.append(b"first").unwrap();
+        assert_eq!(replay.challenge::<Fr>().unwrap(), a);
+        // a different message than the one sent is rejected
+        assert!(replay.append(b"other").is_err());
+        assert_eq!(replay.challenge::<Fr>().unwrap(), b);
+        assert!(replay.challenge::<Fr>().is_err());
+        replay.finish().unwrap();
+    }
+
+    #[test]
+    fn test_replay_rejects_reordered_challenge() {
+        // the prover takes the round challenge before sending its round poly
+        let (mut prover, verifier) = message_channel(ark_std::test_rng());
+        let prover = thread::spawn(move || {
+            prover.append(b"round 0").unwrap();
+            let _: Fr = prover.challenge().unwrap();
+            let _: Fr = prover.challenge().unwrap();
+            prover.append(b"round 1").unwrap();
+        });
+        let mut replay = verifier.serve::<Fr>().unwrap();
+        prover.join().unwrap();
+
+        // the verifier absorbs round 1 before sampling its challenge
+        replay.append(b"round 0").unwrap();
+        replay.challenge::<Fr>().unwrap();
+        assert!(replay.append(b"round 1").is_err());
+    }
+
+    #[test]
+    fn test_replay_finish_rejects_leftovers() {
+        let replay = |events: Vec<Event>| ReplayChannel {
+            events: events.into(),
+        };
+
+        let mut channel = replay(vec![
+            Event::Message(b"first".to_vec()),
+            Event::Message(b"second".to_vec()),
+        ]);
+        channel.append(b"first").unwrap();
+        assert!(channel.finish().is_err());
+
+        let mut channel = replay(vec![
+            Event::Message(b"first".to_vec()),
+            Event::Challenge(vec![0; 32]),
+        ]);
+        channel.append(b"first").unwrap();
+        assert!(channel.finish().is_err());
+
+        // events must be consumed as the kind they were recorded as
+        let mut channel = replay(vec![Event::Challenge(vec![0; 32])]);
+        assert!(channel.append(&[0; 32]).is_err());
+
+        assert!(replay(vec![]).finish().is_ok());
+    }
+
+    #[test]
+    fn test_transcript_snapshot_restore() {
+        let mut transcript = Transcript::new();
+        transcript.append(b"message");
+        let snapshot = transcript.snapshot();
+
+        let first: Fr = transcript.sample_field_element();
+        transcript.restore(&snapshot);
+        assert_eq!(transcript.sample_field_element::<Fr>(), first);
+    }
+}
@@ -1,11 +1,23 @@
 use ark_ff::PrimeField;
 use sha3::{Digest, Keccak256};
 
+pub mod channel;
+pub mod test_vectors;
+
+pub use test_vectors::test_vectors;
+
 // TODO: implement better transcript
+#[derive(Clone)]
 pub struct Transcript {
     hasher: Keccak256,
 }
 
+impl Default for Transcript {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Transcript {
     pub fn new() -> Self {
         Self {
@@ -17,7 +29,21 @@ impl Transcript {
         self.hasher.update(new_data);
     }
 
-    fn sample_challenge(&mut self) -> [u8; 32] {
+    /// Capture the current transcript state
+    pub fn snapshot(&self) -> TranscriptSnapshot {
+        TranscriptSnapshot {
+            hasher: self.hasher.clone(),
+        }
+    }
+
+    /// Rewind the transcript to a previously captured state
+    pub fn restore(&mut self, snapshot: &TranscriptSnapshot) {
+        self.hasher = snapshot.hasher.clone();
+    }
+
+    /// Sample the raw 32 byte challenge digest
+    /// the field element challenges are derived from this, see `test_vectors` for the spec
+    pub fn sample_challenge(&mut self) -> [u8; 32] {
         let mut result_hash = [0; 32];
         result_hash.copy_from_slice(&self.hasher.finalize_reset());
         self.hasher.update(result_hash);
@@ -33,3 +59,22 @@ impl Transcript {
         (0..n).map(|_| self.sample_field_element()).collect()
     }
 }
+
+/// Absorbs everything written, same as calling `append` on each chunk
+/// lets encoders stream large objects into the transcript without buffering them
+impl std::io::Write for Transcript {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.append(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Saved `Transcript` state, see `Transcript::snapshot`
+#[derive(Clone)]
+pub struct TranscriptSnapshot {
+    hasher: Keccak256,
+}
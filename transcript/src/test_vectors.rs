@@ -0,0 +1,133 @@
+//! Transcript test vectors
+//! fixed input sequences and the challenge bytes the `Transcript` derives from them,
+//! independent implementations (e.g. a JS or solidity verifier) can replay these to match
+//! the Fiat-Shamir challenge derivation exactly.
+//!
+//! Derivation:
+//! - the transcript is a running keccak256 hash, `append` absorbs the bytes as is
+//!   (so appending "a" then "b" is the same as appending "ab")
+//! - a challenge is the keccak256 digest of everything absorbed since the last challenge,
+//!   after sampling, the hash is reset and the challenge itself is absorbed as the first input
+//! - field element challenges interpret the 32 digest bytes as a big endian integer
+//!   reduced modulo the field order
+
+/// One step of a test vector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Absorb the given bytes
+    Append(&'static [u8]),
+    /// Sample a challenge, the expected digest is given as a hex string
+    Challenge(&'static str),
+}
+
+/// Named sequence of transcript operations with the expected challenges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestVector {
+    pub name: &'static str,
+    pub steps: &'static [Step],
+}
+
+/// Returns the canonical transcript test vectors
+pub fn test_vectors() -> &'static [TestVector] {
+    TEST_VECTORS
+}
+
+const TEST_VECTORS: &[TestVector] = &[
+    TestVector {
+        name: "empty",
+        steps: &[Step::Challenge(
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+        )],
+    },
+    TestVector {
+        name: "single_append",
+        steps: &[
+            Step::Append(b"zk"),
+            Step::Challenge("db5e8e4e2ffa1a193cfed3f50192fdb61829c03109dbfd01316a227e9ff40e34"),
+        ],
+    },
+    TestVector {
+        name: "split_append",
+        steps: &[
+            Step::Append(b"z"),
+            Step::Append(b"k"),
+            Step::Challenge("db5e8e4e2ffa1a193cfed3f50192fdb61829c03109dbfd01316a227e9ff40e34"),
+        ],
+    },
+    TestVector {
+        name: "chained_challenges",
+        steps: &[
+            Step::Append(b"round 0"),
+            Step::Challenge("fb24455786d14d31d3410eb8eefa3ac810e3f2e2ae12a867d1eae9dba56acd60"),
+            Step::Challenge("2968b7e6206fdeb691250618b8a37e608f87c4e20254640a526a377c16cc1926"),
+            Step::Append(b"round 1"),
+            Step::Challenge("6f81ae791fc2390f3c0a20c10a7137d5e1b10d6339750723390cbd627871e99e"),
+        ],
+    },
+    TestVector {
+        name: "field_element",
+        steps: &[
+            Step::Append(&[
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 10,
+            ]),
+            Step::Challenge("c65a7bb8d6351c1cf70c95a316cc6a92839c986682d98bc35f958f4883f9d2a8"),
+        ],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{test_vectors, Step};
+    use crate::Transcript;
+    use ark_bls12_381::Fr;
+    use ark_ff::PrimeField;
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn test_vectors_match_transcript() {
+        for vector in test_vectors() {
+            let mut transcript = Transcript::new();
+            for step in vector.steps {
+                match step {
+                    Step::Append(bytes) => transcript.append(bytes),
+                    Step::Challenge(expected) => assert_eq!(
+                        to_hex(&transcript.sample_challenge()),
+                        *expected,
+                        "test vector {}",
+                        vector.name
+                    ),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_field_challenge_is_reduced_digest() {
+        let mut bytes_transcript = Transcript::new();
+        let mut field_transcript = Transcript::new();
+        bytes_transcript.append(b"zk");
+        field_transcript.append(b"zk");
+
+        let digest = bytes_transcript.sample_challenge();
+        assert_eq!(
+            field_transcript.sample_field_element::<Fr>(),
+            Fr::from_be_bytes_mod_order(&digest)
+        );
+    }
+
+    #[test]
+    fn test_streamed_writes_match_append() {
+        use std::io::Write;
+
+        let mut appended = Transcript::new();
+        appended.append(b"zk");
+        let mut streamed = Transcript::new();
+        streamed.write_all(b"z").unwrap();
+        streamed.write_all(b"k").unwrap();
+        assert_eq!(appended.sample_challenge(), streamed.sample_challenge());
+    }
+}
@@ -0,0 +1,55 @@
+//! Public API for the workspace
+//! re-exports the maintained implementation of each protocol piece,
+//! depend on this crate rather than on the individual crates
+
+/// Univariate, multilinear (coefficient and evaluation form) and product polynomials
+pub mod polynomial {
+    pub use ::polynomial::hypercube_sum::{HypercubeSum, SumMode};
+    pub use ::polynomial::multilinear::coefficient_form::{
+        CoeffMultilinearPolynomial, InterpolationStrategy,
+    };
+    pub use ::polynomial::multilinear::evaluation_form::MultiLinearPolynomial;
+    pub use ::polynomial::multilinear::integer_evaluation::IntegerEvaluation;
+    pub use ::polynomial::oracle_poly::{Oracle, OraclePoly};
+    pub use ::polynomial::point;
+    pub use ::polynomial::product_poly::{LazyProductPoly, ProductPoly};
+    pub use ::polynomial::sum_poly::SumPoly;
+    pub use ::polynomial::univariate_poly::UnivariatePolynomial;
+    pub use ::polynomial::{Polynomial, SumcheckPolynomial};
+}
+
+/// Polynomial commitment schemes
+pub mod pcs {
+    pub use ::pcs::kzg::{KZGCommitment, KZGParams, KZGProof, KZG};
+    pub use ::pcs::ptau::{load_ptau, load_ptau_file};
+}
+
+/// Sumcheck over product polynomials, full and partial (verifies to subclaim)
+pub mod sumcheck {
+    pub use ::sumcheck::aggregation::{AggregationProof, ClaimAggregator, EvaluationClaim};
+    pub use ::sumcheck::progress::{NoProgress, ProgressObserver};
+    pub use ::sumcheck::prover::SumcheckProver;
+    pub use ::sumcheck::verifier::SumcheckVerifier;
+    pub use ::sumcheck::{
+        hash_commitment, PolyBinding, SecurityConfig, Statement, SubClaim, Sumcheck,
+        SumcheckOptions, SumcheckProof, SumcheckScheme,
+    };
+}
+
+/// Memory usage reporting (see `record_alloc_scope!` in the stat crate)
+pub mod memory {
+    #[cfg(feature = "alloc")]
+    pub use ::stat::memory::CountingAllocator;
+    pub use ::stat::memory::{
+        alloc_stats, check_peak_rss, format_memory_report, peak_rss_bytes, print_memory_report,
+        take_memory_report, AllocStats, ScopeRecord,
+    };
+}
+
+/// Fiat-Shamir transcript and interactive prover/verifier channels
+pub mod transcript {
+    pub use ::transcript::channel::{
+        message_channel, InteractiveChannel, ProverChannel, ReplayChannel, VerifierChannel,
+    };
+    pub use ::transcript::{Transcript, TranscriptSnapshot};
+}